@@ -0,0 +1,29 @@
+use std::thread::{self, ThreadId};
+
+/// Remembers the thread of the first poll and panics if a later poll arrives from another one.
+#[derive(Default)]
+pub(crate) struct ThreadAffinity {
+    enabled: bool,
+    owner: Option<ThreadId>,
+}
+
+impl ThreadAffinity {
+    pub(crate) fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub(crate) fn check(&mut self, op: &str) {
+        if !self.enabled {
+            return;
+        }
+        let current = thread::current().id();
+        match self.owner {
+            None => self.owner = Some(current),
+            Some(owner) if owner != current => panic!(
+                "PinCursor: {} polled on thread {:?}, but the first poll happened on thread {:?}",
+                op, current, owner
+            ),
+            Some(_) => {}
+        }
+    }
+}
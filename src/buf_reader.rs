@@ -0,0 +1,201 @@
+use std::io::{IoSliceMut, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{BufRead, Read};
+use pin_project_lite::pin_project;
+
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+pin_project! {
+    /// A `!Unpin` buffered reader whose buffer can be inspected.
+    ///
+    /// The inner reader may itself be `!Unpin`, e.g. a [`PinCursor`](crate::PinCursor).
+    /// Reads that fit into the buffered bytes never touch the inner reader; the inner reader is
+    /// only polled when the buffer is empty, and reads at least as large as the capacity
+    /// bypass an empty buffer altogether, like `async_std::io::BufReader` does.
+    pub struct PinBufReader<R> {
+        #[pin]
+        inner: R,
+        buf: Box<[u8]>,
+        pos: usize,
+        filled: usize,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<R> PinBufReader<R>
+    where R: Read
+{
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Self {
+            inner,
+            buf: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+            _p: PhantomPinned,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner
+    }
+
+    /// Returns the inner reader, discarding any buffered bytes.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// The bytes that have been buffered but not consumed yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+impl<R> Read for PinBufReader<R>
+    where R: Read
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        if self.pos == self.filled && buf.len() >= self.buf.len() {
+            let this = self.project();
+            *this.pos = 0;
+            *this.filled = 0;
+            return this.inner.poll_read(cx, buf);
+        }
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_read_vectored(mut self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
+        let total: usize = bufs.iter().map(|b| b.len()).sum();
+        if self.pos == self.filled && total >= self.buf.len() {
+            let this = self.project();
+            *this.pos = 0;
+            *this.filled = 0;
+            return this.inner.poll_read_vectored(cx, bufs);
+        }
+        let mut available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let mut n = 0;
+        for buf in bufs {
+            let len = available.len().min(buf.len());
+            buf[..len].copy_from_slice(&available[..len]);
+            available = &available[len..];
+            n += len;
+        }
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<R> BufRead for PinBufReader<R>
+    where R: Read
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        let this = self.project();
+        if *this.pos >= *this.filled {
+            *this.filled = ready!(this.inner.poll_read(cx, this.buf))?;
+            *this.pos = 0;
+        }
+        Poll::Ready(Ok(&this.buf[*this.pos..*this.filled]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        *this.pos = (*this.pos + amt).min(*this.filled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use async_std::io::Cursor;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+    use crate::PinCursor;
+
+    use super::*;
+
+    fn reader(capacity: usize) -> Pin<Box<PinBufReader<PinCursor<Vec<u8>>>>> {
+        let data = (0u8..10).collect();
+        Box::pin(PinBufReader::with_capacity(capacity, PinCursor::wrap(Cursor::new(data))))
+    }
+
+    #[test]
+    fn impls() {
+        assert_not_impl_all!(PinBufReader<Cursor<Vec<u8>>>: Unpin);
+        assert_impl_all!(PinBufReader<PinCursor<Vec<u8>>>: Read, BufRead);
+    }
+
+    #[test]
+    fn refill_boundaries() {
+        let mut reader = reader(4);
+        assert_eq!(reader.capacity(), 4);
+        let mut buf = [0u8; 3];
+        block_on(async {
+            assert_eq!(reader.read(&mut buf[..1]).await.unwrap(), 1);
+            assert_eq!(reader.get_ref().bytes_read(), 4);
+            assert_eq!(reader.buffer(), [1, 2, 3]);
+            // Served from the buffer without touching the inner cursor.
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 3);
+            assert_eq!(buf, [1, 2, 3]);
+            assert_eq!(reader.get_ref().bytes_read(), 4);
+            assert!(reader.buffer().is_empty());
+            // An empty buffer is refilled on the next small read...
+            assert_eq!(reader.read(&mut buf[..2]).await.unwrap(), 2);
+            assert_eq!(reader.get_ref().position(), 8);
+            assert_eq!(reader.buffer(), [6, 7]);
+            // ...and a short buffered tail is not topped up.
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 2);
+            assert_eq!(reader.get_ref().position(), 8);
+        });
+    }
+
+    #[test]
+    fn large_reads_bypass_the_buffer() {
+        let mut reader = reader(4);
+        let mut buf = [0u8; 6];
+        assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 6);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5]);
+        assert!(reader.buffer().is_empty());
+    }
+
+    fn fill_buf(reader: Pin<&mut PinBufReader<PinCursor<Vec<u8>>>>) -> Vec<u8> {
+        let mut cx = Context::from_waker(Waker::noop());
+        match reader.poll_fill_buf(&mut cx) {
+            Poll::Ready(Ok(buf)) => buf.to_vec(),
+            _ => panic!("fill_buf did not complete"),
+        }
+    }
+
+    #[test]
+    fn consume() {
+        let mut reader = reader(4);
+        assert_eq!(fill_buf(reader.as_mut()), [0, 1, 2, 3]);
+        reader.as_mut().consume(3);
+        assert_eq!(reader.buffer(), [3]);
+        assert_eq!(fill_buf(reader.as_mut()), [3]);
+        reader.as_mut().consume(10);
+        assert_eq!(fill_buf(reader.as_mut()), [4, 5, 6, 7]);
+        assert_eq!(reader.get_ref().position(), 8);
+    }
+}
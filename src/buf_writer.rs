@@ -0,0 +1,241 @@
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{Seek, Write};
+use pin_project_lite::pin_project;
+
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+struct Staging {
+    data: Vec<u8>,
+    // How much of `data` has already reached the inner writer.
+    written: usize,
+    assert_flushed: bool,
+}
+
+impl Staging {
+    fn unflushed(&self) -> &[u8] {
+        &self.data[self.written..]
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if self.assert_flushed && !self.unflushed().is_empty() && !std::thread::panicking() {
+            panic!("PinBufWriter dropped with {} unflushed bytes", self.unflushed().len());
+        }
+    }
+}
+
+pin_project! {
+    /// A `!Unpin` buffered writer with explicit flush boundaries.
+    ///
+    /// A write is buffered if it fits into the remaining capacity. Otherwise the buffered bytes
+    /// are written out first, and then the new bytes are buffered, or, if they are larger than
+    /// the whole capacity, written straight to the inner writer. Besides that, buffered bytes
+    /// only reach the inner writer on flush, close, or before a seek.
+    pub struct PinBufWriter<W> {
+        #[pin]
+        inner: W,
+        staging: Staging,
+        capacity: usize,
+        flushed_bytes: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<W> PinBufWriter<W>
+    where W: Write
+{
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self {
+            inner,
+            staging: Staging { data: Vec::with_capacity(capacity), written: 0, assert_flushed: false },
+            capacity,
+            flushed_bytes: 0,
+            _p: PhantomPinned,
+        }
+    }
+
+    /// Makes dropping the writer with unflushed bytes panic.
+    pub fn assert_flushed_on_drop(mut self) -> Self {
+        self.staging.assert_flushed = true;
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().inner
+    }
+
+    /// Returns the inner writer, discarding any unflushed bytes.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// The bytes that have been written to this writer but not to the inner one yet.
+    pub fn buffer(&self) -> &[u8] {
+        self.staging.unflushed()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The total number of bytes that have reached the inner writer.
+    pub fn flushed_bytes(&self) -> u64 {
+        self.flushed_bytes
+    }
+
+    fn poll_flush_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut this = self.project();
+        while !this.staging.unflushed().is_empty() {
+            let n = ready!(this.inner.as_mut().poll_write(cx, this.staging.unflushed()))?;
+            if n == 0 {
+                return Poll::Ready(Err(Error::new(ErrorKind::WriteZero, "failed to write the buffered data")));
+            }
+            this.staging.written += n;
+            *this.flushed_bytes += n as u64;
+        }
+        this.staging.data.clear();
+        this.staging.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W> Write for PinBufWriter<W>
+    where W: Write
+{
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        if self.staging.data.len() + buf.len() > self.capacity {
+            ready!(self.as_mut().poll_flush_buf(cx))?;
+        }
+        let this = self.project();
+        if buf.len() > *this.capacity {
+            let n = ready!(this.inner.poll_write(cx, buf))?;
+            *this.flushed_bytes += n as u64;
+            Poll::Ready(Ok(n))
+        } else {
+            this.staging.data.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        ready!(self.as_mut().poll_flush_buf(cx))?;
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        ready!(self.as_mut().poll_flush_buf(cx))?;
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<W> Seek for PinBufWriter<W>
+    where W: Write + Seek
+{
+    /// Writes out the buffered bytes, then seeks the inner writer.
+    fn poll_seek(mut self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        ready!(self.as_mut().poll_flush_buf(cx))?;
+        self.project().inner.poll_seek(cx, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+
+    use async_std::io::Cursor;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+    use crate::PinCursor;
+
+    use super::*;
+
+    fn writer(capacity: usize) -> Pin<Box<PinBufWriter<PinCursor<Vec<u8>>>>> {
+        Box::pin(PinBufWriter::with_capacity(capacity, PinCursor::wrap(Cursor::new(Vec::new()))))
+    }
+
+    fn inner(writer: &PinBufWriter<PinCursor<Vec<u8>>>) -> &[u8] {
+        writer.get_ref().get_ref()
+    }
+
+    #[test]
+    fn impls() {
+        assert_not_impl_all!(PinBufWriter<Cursor<Vec<u8>>>: Unpin);
+        assert_impl_all!(PinBufWriter<PinCursor<Vec<u8>>>: Write, Seek);
+    }
+
+    #[test]
+    fn below_capacity() {
+        let mut writer = writer(4);
+        block_on(async {
+            assert_eq!(writer.write(&[1, 2, 3]).await.unwrap(), 3);
+            assert_eq!(writer.buffer(), [1, 2, 3]);
+            assert!(inner(&writer).is_empty());
+            writer.flush().await.unwrap();
+        });
+        assert_eq!(inner(&writer), [1, 2, 3]);
+        assert!(writer.buffer().is_empty());
+        assert_eq!(writer.flushed_bytes(), 3);
+    }
+
+    #[test]
+    fn at_capacity() {
+        let mut writer = writer(4);
+        block_on(async {
+            writer.write_all(&[1, 2, 3, 4]).await.unwrap();
+            assert!(inner(&writer).is_empty());
+            // The next byte overflows: the full buffer goes out, the new byte is buffered.
+            writer.write_all(&[5]).await.unwrap();
+            assert_eq!(inner(&writer), [1, 2, 3, 4]);
+            assert_eq!(writer.buffer(), [5]);
+            poll_fn(|cx| writer.as_mut().poll_close(cx)).await.unwrap();
+        });
+        assert_eq!(inner(&writer), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn above_capacity() {
+        let mut writer = writer(4);
+        block_on(async {
+            writer.write_all(&[1]).await.unwrap();
+            assert_eq!(writer.write(&[2, 3, 4, 5, 6]).await.unwrap(), 5);
+        });
+        assert_eq!(inner(&writer), [1, 2, 3, 4, 5, 6]);
+        assert!(writer.buffer().is_empty());
+        assert_eq!(writer.flushed_bytes(), 6);
+    }
+
+    #[test]
+    fn flush_before_seek() {
+        let mut writer = writer(4);
+        block_on(async {
+            writer.write_all(&[1, 2, 3]).await.unwrap();
+            assert_eq!(writer.seek(SeekFrom::Start(1)).await.unwrap(), 1);
+            writer.write_all(&[9]).await.unwrap();
+            writer.flush().await.unwrap();
+        });
+        assert_eq!(inner(&writer), [1, 9, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "PinBufWriter dropped with 2 unflushed bytes")]
+    fn unflushed_drop() {
+        let mut writer = Box::pin(PinBufWriter::with_capacity(4, PinCursor::wrap(Cursor::new(Vec::new()))).assert_flushed_on_drop());
+        block_on(writer.write_all(&[1, 2])).unwrap();
+    }
+}
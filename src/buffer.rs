@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Result};
+use std::iter;
+
+#[cfg(feature = "bytes")]
+use bytes::BytesMut;
+
+/// Backing storage that a [`PinCursor`](crate::PinCursor) can write into.
+///
+/// Growable buffers (`Vec<u8>`, `&mut Vec<u8>`) extend on writes past their end, zero-filling
+/// any gap, just like `std::io::Cursor` does. Fixed-size buffers (`&mut [u8]`, `[u8; N]`)
+/// accept only as many bytes as fit before their end, so writes running past the end are short,
+/// and a write starting at or after the end returns `Ok(0)`.
+///
+/// A `String` accepts a write only if the result is still valid UTF-8, and fails it with
+/// `ErrorKind::InvalidData`, leaving the string untouched, otherwise.
+///
+/// A borrowed `Cow<[u8]>` is copied into an owned vector on the first write, which then
+/// grows like `Vec<u8>`; the borrowed data itself is never modified.
+///
+/// With the `bytes` feature, a `BytesMut` grows like `Vec<u8>`, reallocating just as it would
+/// in production code. It has no exact reservation, so
+/// [`with_exact_growth`](crate::PinCursor::with_exact_growth) reserves through `BytesMut::reserve`.
+pub trait Buffer: AsRef<[u8]> {
+    /// Writes as much of `buf` as possible at offset `pos`, returning the number of bytes written.
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize>;
+
+    /// Makes room for exactly `additional` more bytes past the end, without amortized
+    /// over-allocation. Buffers that cannot grow ignore this.
+    fn reserve_exact(&mut self, additional: usize) {
+        let _ = additional;
+    }
+
+    /// Checks, without changing anything, that `write_at(0, data)` would accept all of `data`,
+    /// where `data` is at least as long as the buffer. Buffers that can shorten or reject
+    /// writes override this; the default accepts anything.
+    fn check_contents(&self, data: &[u8]) -> Result<()> {
+        let _ = data;
+        Ok(())
+    }
+}
+
+impl Buffer for Vec<u8> {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        write_growable(self, pos, buf)
+    }
+
+    fn reserve_exact(&mut self, additional: usize) {
+        Vec::reserve_exact(self, additional)
+    }
+}
+
+impl Buffer for &mut Vec<u8> {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        (**self).write_at(pos, buf)
+    }
+
+    fn reserve_exact(&mut self, additional: usize) {
+        (**self).reserve_exact(additional)
+    }
+}
+
+impl Buffer for String {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        let mut bytes = self.as_bytes().to_vec();
+        let n = bytes.write_at(pos, buf)?;
+        *self = String::from_utf8(bytes).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        Ok(n)
+    }
+
+    fn check_contents(&self, data: &[u8]) -> Result<()> {
+        std::str::from_utf8(data).map(drop).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+impl Buffer for Cow<'_, [u8]> {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        self.to_mut().write_at(pos, buf)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Buffer for BytesMut {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        write_growable(self, pos, buf)
+    }
+
+    fn reserve_exact(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+}
+
+impl Buffer for &mut [u8] {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        Ok(write_fixed(self, pos, buf))
+    }
+
+    fn check_contents(&self, data: &[u8]) -> Result<()> {
+        check_fixed(self, data)
+    }
+}
+
+impl<const N: usize> Buffer for [u8; N] {
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+        Ok(write_fixed(self, pos, buf))
+    }
+
+    fn check_contents(&self, data: &[u8]) -> Result<()> {
+        check_fixed(self, data)
+    }
+}
+
+fn check_fixed(dst: &[u8], data: &[u8]) -> Result<()> {
+    if data.len() > dst.len() {
+        return Err(Error::new(
+            ErrorKind::WriteZero,
+            format!("{} bytes do not fit into a buffer of length {}", data.len(), dst.len()),
+        ));
+    }
+    Ok(())
+}
+
+// Writes all of `buf` at `pos`, overwriting what is there, extending the buffer past its end
+// and zero-filling any gap before `pos`.
+fn write_growable<B>(dst: &mut B, pos: u64, buf: &[u8]) -> Result<usize>
+    where B: AsMut<[u8]> + Extend<u8> + for<'a> Extend<&'a u8>
+{
+    let pos = usize::try_from(pos)
+        .ok()
+        .filter(|pos| pos.checked_add(buf.len()).is_some_and(|end| end <= isize::MAX as usize))
+        .ok_or_else(|| Error::new(
+            ErrorKind::InvalidInput,
+            "cursor position exceeds maximum possible vector length",
+        ))?;
+    let len = dst.as_mut().len();
+    if len < pos {
+        dst.extend(iter::repeat_n(0, pos - len));
+    }
+    let existing = &mut dst.as_mut()[pos..];
+    let overlap = existing.len().min(buf.len());
+    existing[..overlap].copy_from_slice(&buf[..overlap]);
+    dst.extend(&buf[overlap..]);
+    Ok(buf.len())
+}
+
+fn write_fixed(dst: &mut [u8], pos: u64, buf: &[u8]) -> usize {
+    let start = usize::try_from(pos).unwrap_or(usize::MAX).min(dst.len());
+    let n = (dst.len() - start).min(buf.len());
+    dst[start..start + n].copy_from_slice(&buf[..n]);
+    n
+}
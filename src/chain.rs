@@ -0,0 +1,150 @@
+use std::io::Result;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{BufRead, Read};
+use pin_project_lite::pin_project;
+
+use crate::PinCursor;
+
+pin_project! {
+    /// A `!Unpin` reader that yields the contents of several cursors one after another.
+    ///
+    /// Each segment keeps its own fault injection settings, so a fault can be placed in,
+    /// say, the body of a message but not in its header. Segments that are empty or already
+    /// at their end are skipped; reads return `Ok(0)` only after the last segment.
+    pub struct ChainCursor<T> {
+        segments: Vec<Pin<Box<PinCursor<T>>>>,
+        active: usize,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> ChainCursor<T>
+    where T: AsRef<[u8]>
+{
+    pub fn new(segments: Vec<PinCursor<T>>) -> Self {
+        Self {
+            segments: segments.into_iter().map(Box::pin).collect(),
+            active: 0,
+            _p: PhantomPinned,
+        }
+    }
+
+    /// The index of the segment that reads currently come from.
+    ///
+    /// Equals the number of segments once all of them are exhausted.
+    pub fn active_segment(&self) -> usize {
+        self.active
+    }
+
+    pub fn segments(&self) -> &[Pin<Box<PinCursor<T>>>] {
+        &self.segments
+    }
+}
+
+impl<T> Read for ChainCursor<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        while let Some(segment) = this.segments.get_mut(*this.active) {
+            let n = ready!(segment.as_mut().poll_read(cx, buf))?;
+            if n > 0 {
+                return Poll::Ready(Ok(n));
+            }
+            *this.active += 1;
+        }
+        Poll::Ready(Ok(0))
+    }
+}
+
+impl<T> BufRead for ChainCursor<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        let this = self.project();
+        while let Some(segment) = this.segments.get_mut(*this.active) {
+            // Each segment is polled once: the bytes it hands out are the start of its unread
+            // part, so they are borrowed again from there instead of through a second poll.
+            let len = ready!(segment.as_mut().poll_fill_buf(cx))?.len();
+            if len > 0 {
+                return Poll::Ready(Ok(&this.segments[*this.active].unread()[..len]));
+            }
+            *this.active += 1;
+        }
+        Poll::Ready(Ok(&[]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        if let Some(segment) = this.segments.get_mut(*this.active) {
+            segment.as_mut().consume(amt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::{Cursor, Seek, Write};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    fn segment(data: &[u8]) -> PinCursor<Vec<u8>> {
+        PinCursor::wrap(Cursor::new(data.to_vec()))
+    }
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(ChainCursor<Vec<u8>>: Unpin, Write, Seek);
+        assert_impl_all!(ChainCursor<Vec<u8>>: Read, BufRead);
+    }
+
+    #[test]
+    fn skips_empty_segments() {
+        let mut chain = Box::pin(ChainCursor::new(vec![segment(b"head"), segment(b""), segment(b"tail")]));
+        let mut out = Vec::new();
+        assert_eq!(block_on(chain.read_to_end(&mut out)).unwrap(), 8);
+        assert_eq!(out, b"headtail");
+        assert_eq!(chain.active_segment(), 3);
+    }
+
+    #[test]
+    fn lines_across_segments() {
+        let mut chain = Box::pin(ChainCursor::new(vec![segment(b"a\nb"), segment(b""), segment(b"c\n")]));
+        let mut line = String::new();
+        assert_eq!(block_on(chain.read_line(&mut line)).unwrap(), 2);
+        assert_eq!(block_on(chain.read_line(&mut line)).unwrap(), 3);
+        assert_eq!(line, "a\nbc\n");
+    }
+
+    #[test]
+    fn fault_in_middle_segment() {
+        let segments = vec![segment(b"header"), segment(b"body").with_read_schedule(vec![2]).fail_read_after(2), segment(b"trailer")];
+        let mut chain = Box::pin(ChainCursor::new(segments));
+        let mut out = Vec::new();
+        assert!(block_on(chain.read_to_end(&mut out)).is_err());
+        assert_eq!(out, b"headerbo");
+        assert_eq!(chain.active_segment(), 1);
+        assert_eq!(chain.segments()[1].position(), 2);
+    }
+
+    #[test]
+    fn fill_buf_polls_segment_once() {
+        // A second poll would be the one that gets interrupted.
+        let mut chain = Box::pin(ChainCursor::new(vec![segment(b""), segment(b"body").with_interrupt_every(2)]));
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        let Poll::Ready(Ok(buf)) = chain.as_mut().poll_fill_buf(&mut cx) else { panic!("fill_buf did not return data") };
+        assert_eq!(buf, b"body");
+        chain.as_mut().consume(4);
+        assert_eq!(chain.segments()[1].position(), 4);
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+/// Virtual time spent in IO: every completed operation adds the cost reported by the clock.
+#[derive(Default)]
+pub(crate) struct VirtualClock {
+    cost: Option<Box<dyn Fn() -> Duration + Send + Sync>>,
+    elapsed: Duration,
+}
+
+impl VirtualClock {
+    pub(crate) fn set_cost(&mut self, cost: impl Fn() -> Duration + Send + Sync + 'static) {
+        self.cost = Some(Box::new(cost));
+    }
+
+    pub(crate) fn tick(&mut self) {
+        if let Some(cost) = &self.cost {
+            self.elapsed = self.elapsed.saturating_add(cost());
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
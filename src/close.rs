@@ -0,0 +1,69 @@
+use std::io::{Error, ErrorKind, Result};
+
+/// What reads from a closed cursor do under [`with_strict_close`](crate::PinCursor::with_strict_close).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadsAfterClose {
+    /// Reads go on as if the cursor was still open.
+    Allowed,
+    /// Reads return EOF.
+    Eof,
+}
+
+/// Tracks whether a cursor has been closed, and what that means for further operations.
+#[derive(Default)]
+pub(crate) struct CloseState {
+    closed: bool,
+    strict: Option<ReadsAfterClose>,
+    error_after_close: Option<ErrorKind>,
+    expect_closed: bool,
+}
+
+impl CloseState {
+    pub(crate) fn set_strict(&mut self, reads: ReadsAfterClose) {
+        self.strict = Some(reads);
+    }
+
+    pub(crate) fn set_error_after_close(&mut self, kind: ErrorKind) {
+        self.error_after_close = Some(kind);
+    }
+
+    pub(crate) fn expect_closed(&mut self) {
+        self.expect_closed = true;
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.closed = true;
+    }
+
+    pub(crate) fn check_write(&self) -> Result<()> {
+        match (self.closed, self.error_after_close, self.strict) {
+            (true, Some(kind), _) => Err(Error::new(kind, "write after close")),
+            (true, None, Some(_)) => Err(Error::new(ErrorKind::BrokenPipe, "write after close")),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_flush(&self) -> Result<()> {
+        match (self.closed, self.error_after_close, self.strict) {
+            (true, Some(kind), _) => Err(Error::new(kind, "flush after close")),
+            (true, None, Some(_)) => Err(Error::new(ErrorKind::BrokenPipe, "flush after close")),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn reads_eof(&self) -> bool {
+        self.closed && self.strict == Some(ReadsAfterClose::Eof)
+    }
+}
+
+impl Drop for CloseState {
+    fn drop(&mut self) {
+        if self.expect_closed && !self.closed && !std::thread::panicking() {
+            panic!("PinCursor dropped without being closed");
+        }
+    }
+}
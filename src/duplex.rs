@@ -0,0 +1,112 @@
+use std::io::Result;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{Read, Write};
+use pin_project_lite::pin_project;
+
+use crate::pipe::{PipeReader, PipeWriter, pipe};
+
+pin_project! {
+    /// One end of an in-memory, unbounded, bidirectional byte stream. See [`pin_duplex`].
+    pub struct PinDuplex {
+        reader: PipeReader,
+        writer: PipeWriter,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+/// Creates a connected pair of `!Unpin` endpoints: whatever one writes, the other reads.
+///
+/// Reading from an endpoint with nothing to read returns `Poll::Pending` until the peer
+/// writes something. Once the peer is closed (or dropped), reads drain what is left
+/// and then return `Ok(0)`. Writing after closing fails with `ErrorKind::BrokenPipe`.
+pub fn pin_duplex() -> (PinDuplex, PinDuplex) {
+    let (a_writer, b_reader) = pipe(None);
+    let (b_writer, a_reader) = pipe(None);
+    (
+        PinDuplex { reader: a_reader, writer: a_writer, _p: PhantomPinned },
+        PinDuplex { reader: b_reader, writer: b_writer, _p: PhantomPinned },
+    )
+}
+
+impl Read for PinDuplex {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        self.project().reader.poll_read(cx, buf)
+    }
+}
+
+impl Write for PinDuplex {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        self.project().writer.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().writer.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::io::ErrorKind;
+    use std::task::Waker;
+
+    use async_std::io::prelude::*;
+    use async_std::task::{block_on, spawn};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(PinDuplex: Unpin);
+        assert_impl_all!(PinDuplex: Read, Write, Send);
+    }
+
+    #[test]
+    fn request_response() {
+        let (client, server) = pin_duplex();
+        let (mut client, mut server) = (Box::pin(client), Box::pin(server));
+        block_on(async {
+            let server = spawn(async move {
+                let mut request = [0u8; 4];
+                server.read_exact(&mut request).await.unwrap();
+                assert_eq!(&request, b"ping");
+                server.write_all(b"pong").await.unwrap();
+                poll_fn(|cx| server.as_mut().poll_close(cx)).await.unwrap();
+            });
+            client.write_all(b"ping").await.unwrap();
+            let mut response = Vec::new();
+            client.read_to_end(&mut response).await.unwrap();
+            assert_eq!(response, b"pong");
+            server.await;
+        });
+    }
+
+    #[test]
+    fn pending_until_written() {
+        let (a, b) = pin_duplex();
+        let (mut a, mut b) = (Box::pin(a), Box::pin(b));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 4];
+        assert!(b.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        assert!(matches!(a.as_mut().poll_write(&mut cx, &[1, 2]), Poll::Ready(Ok(2))));
+        assert!(matches!(a.as_mut().poll_close(&mut cx), Poll::Ready(Ok(()))));
+        assert!(matches!(b.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(2))));
+        assert!(matches!(b.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(0))));
+        match a.as_mut().poll_write(&mut cx, &[3]) {
+            Poll::Ready(Err(e)) => assert_eq!(e.kind(), ErrorKind::BrokenPipe),
+            _ => panic!("write after close succeeded"),
+        }
+        // The other direction is still open.
+        assert!(matches!(b.as_mut().poll_write(&mut cx, &[3]), Poll::Ready(Ok(1))));
+    }
+}
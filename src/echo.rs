@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use async_std::io::{Read, Write};
+use pin_project_lite::pin_project;
+
+use crate::Stats;
+
+pin_project! {
+    /// A `!Unpin` loopback: everything written to it can be read back from it, in FIFO order.
+    ///
+    /// This lets full-duplex code talk to itself in a single task. Reads from an empty echo
+    /// return `Poll::Pending` until something is written or the echo is closed; after
+    /// [`close`](EchoCursor::close), reads drain what is left and then return `Ok(0)`,
+    /// and writes fail with `ErrorKind::BrokenPipe`. With a capacity, writes to a full echo
+    /// return `Poll::Pending` until reads make room.
+    pub struct EchoCursor {
+        queue: VecDeque<u8>,
+        capacity: Option<usize>,
+        closed: bool,
+        read_waker: Option<Waker>,
+        write_waker: Option<Waker>,
+        stats: Stats,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl EchoCursor {
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            capacity: None,
+            closed: false,
+            read_waker: None,
+            write_waker: None,
+            stats: Stats::default(),
+            _p: PhantomPinned,
+        }
+    }
+
+    /// Creates an echo that holds at most `capacity` unread bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { capacity: Some(capacity), ..Self::new() }
+    }
+
+    /// The number of bytes written but not read yet.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Counters for both directions.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Stops accepting writes: once the queue is drained, reads return `Ok(0)`.
+    pub fn close(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.closed = true;
+        if let Some(waker) = this.read_waker.take() {
+            waker.wake();
+        }
+        if let Some(waker) = this.write_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for EchoCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for EchoCursor {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if this.queue.is_empty() {
+            if *this.closed {
+                return Poll::Ready(Ok(0));
+            }
+            *this.read_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(this.queue.len());
+        for (dst, src) in buf.iter_mut().zip(this.queue.drain(..n)) {
+            *dst = src;
+        }
+        this.stats.bytes_read += n as u64;
+        this.stats.read_calls += 1;
+        if let Some(waker) = this.write_waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Write for EchoCursor {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if *this.closed {
+            return Poll::Ready(Err(Error::new(ErrorKind::BrokenPipe, "write to a closed echo")));
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let room = match *this.capacity {
+            Some(capacity) => capacity.saturating_sub(this.queue.len()),
+            None => usize::MAX,
+        };
+        if room == 0 {
+            *this.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(room);
+        this.queue.extend(&buf[..n]);
+        this.stats.bytes_written += n as u64;
+        this.stats.write_calls += 1;
+        if let Some(waker) = this.read_waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Same as [`close`](EchoCursor::close).
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use async_std::io::Seek;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(EchoCursor: Unpin, Seek);
+        assert_impl_all!(EchoCursor: Read, Write, Send);
+    }
+
+    #[test]
+    fn frames_in_order() {
+        let mut echo = Box::pin(EchoCursor::new());
+        let mut frame = [0u8; 4];
+        block_on(async {
+            echo.write_all(b"frm1").await.unwrap();
+            echo.write_all(b"frm2").await.unwrap();
+            echo.read_exact(&mut frame).await.unwrap();
+            assert_eq!(&frame, b"frm1");
+            echo.write_all(b"frm3").await.unwrap();
+            echo.read_exact(&mut frame).await.unwrap();
+            assert_eq!(&frame, b"frm2");
+            echo.as_mut().close();
+            assert!(echo.write(b"x").await.is_err());
+            let mut rest = Vec::new();
+            echo.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, b"frm3");
+        });
+        assert_eq!(echo.stats(), Stats { bytes_read: 12, bytes_written: 12, read_calls: 3, write_calls: 3 });
+    }
+
+    #[test]
+    fn backpressure() {
+        let mut echo = Box::pin(EchoCursor::with_capacity(3));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 2];
+        assert!(echo.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        assert!(matches!(echo.as_mut().poll_write(&mut cx, b"abcd"), Poll::Ready(Ok(3))));
+        assert!(echo.as_mut().poll_write(&mut cx, b"d").is_pending());
+        assert!(matches!(echo.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(2))));
+        assert!(matches!(echo.as_mut().poll_write(&mut cx, b"d"), Poll::Ready(Ok(1))));
+        assert_eq!(echo.len(), 2);
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+enum Expectation {
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+    Seek(SeekFrom),
+    Flush,
+    Close,
+    Error(ErrorKind),
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expectation::Read(bytes) => write!(f, "read of {:?}", bytes),
+            Expectation::Write(bytes) => write!(f, "write of {:?}", bytes),
+            Expectation::Seek(pos) => write!(f, "seek to {:?}", pos),
+            Expectation::Flush => f.write_str("flush"),
+            Expectation::Close => f.write_str("close"),
+            Expectation::Error(kind) => write!(f, "any operation failing with {:?}", kind),
+        }
+    }
+}
+
+struct Script {
+    expected: VecDeque<Expectation>,
+    // Index of the expectation at the front of the queue, for error messages.
+    index: usize,
+    position: u64,
+    len: u64,
+}
+
+impl Script {
+    fn advance(&mut self, n: usize) {
+        self.position += n as u64;
+        self.len = self.len.max(self.position);
+    }
+
+    fn complete(&mut self) {
+        self.expected.pop_front();
+        self.index += 1;
+    }
+
+    fn fail(&self, got: &str) -> ! {
+        match self.expected.front() {
+            Some(expected) => panic!("ExpectedCursor: operation #{}: expected {}, got {}", self.index, expected, got),
+            None => panic!("ExpectedCursor: operation #{}: no more operations expected, got {}", self.index, got),
+        }
+    }
+
+    // Returns the injected error if the next expectation is `then_error`.
+    fn take_error(&mut self) -> Option<Error> {
+        if let Some(Expectation::Error(kind)) = self.expected.front() {
+            let kind = *kind;
+            self.complete();
+            Some(Error::new(kind, "ExpectedCursor: injected error"))
+        } else {
+            None
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if let Some(e) = self.take_error() {
+            return Err(e);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let bytes = match self.expected.front_mut() {
+            Some(Expectation::Read(bytes)) => bytes,
+            _ => self.fail(&format!("read into a buffer of {} bytes", buf.len())),
+        };
+        let n = bytes.len().min(buf.len());
+        buf[..n].copy_from_slice(&bytes[..n]);
+        bytes.drain(..n);
+        if bytes.is_empty() {
+            self.complete();
+        }
+        self.advance(n);
+        Ok(n)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if let Some(e) = self.take_error() {
+            return Err(e);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let bytes = match self.expected.front_mut() {
+            Some(Expectation::Write(bytes)) => bytes,
+            _ => self.fail(&format!("write of {:?}", buf)),
+        };
+        let n = bytes.len().min(buf.len());
+        if bytes[..n] != buf[..n] {
+            let got = format!("write of {:?} (expected prefix {:?})", buf, &bytes[..n]);
+            self.fail(&got);
+        }
+        bytes.drain(..n);
+        if bytes.is_empty() {
+            self.complete();
+        }
+        self.advance(n);
+        Ok(n)
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        if let Some(e) = self.take_error() {
+            return Err(e);
+        }
+        match self.expected.front() {
+            Some(Expectation::Seek(expected)) if *expected == pos => {}
+            _ => self.fail(&format!("seek to {:?}", pos)),
+        }
+        self.complete();
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => (n, 0),
+            SeekFrom::Current(n) => (self.position, n),
+            SeekFrom::End(n) => (self.len, n),
+        };
+        let target = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.unsigned_abs())
+        };
+        match target {
+            Some(target) => {
+                self.position = target;
+                Ok(target)
+            }
+            None => Err(Error::new(ErrorKind::InvalidInput, "ExpectedCursor: invalid seek to a negative or overflowing position")),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(e) = self.take_error() {
+            return Err(e);
+        }
+        match self.expected.front() {
+            Some(Expectation::Flush) => self.complete(),
+            _ => self.fail("flush"),
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if let Some(e) = self.take_error() {
+            return Err(e);
+        }
+        match self.expected.front() {
+            Some(Expectation::Close) => self.complete(),
+            _ => self.fail("close"),
+        }
+        Ok(())
+    }
+
+    fn verify(&self) {
+        if let Some(expected) = self.expected.front() {
+            panic!("ExpectedCursor: {} expectation(s) not met, starting with operation #{}: {}", self.expected.len(), self.index, expected);
+        }
+    }
+}
+
+impl Drop for Script {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.verify();
+        }
+    }
+}
+
+pin_project! {
+    /// A strict `!Unpin` mock that only accepts an exact, pre-recorded sequence of operations.
+    ///
+    /// Expectations are consumed in order. A read or write expectation may be satisfied by
+    /// several partial calls; any operation that does not match the next expectation panics
+    /// with a message naming the operation index, what was expected and what arrived.
+    /// Unmet expectations panic on [`verify`](ExpectedCursor::verify) or when the cursor is dropped.
+    ///
+    /// Reads and writes with an empty buffer are answered with `Ok(0)` without consulting
+    /// the script. `SeekFrom::End` is resolved against the furthest offset read or written so far.
+    ///
+    /// ```
+    /// # use async_std::task::block_on;
+    /// use pin_cursor::ExpectedCursor;
+    /// use async_std::io::prelude::*;
+    /// use std::io::SeekFrom;
+    ///
+    /// let mut cursor = Box::pin(ExpectedCursor::new()
+    ///     .expect_write(b"ping")
+    ///     .expect_seek(SeekFrom::Start(0))
+    ///     .expect_read(b"pong"));
+    /// # block_on(
+    /// async {
+    ///     cursor.write_all(b"ping").await.unwrap();
+    ///     cursor.seek(SeekFrom::Start(0)).await.unwrap();
+    ///     let mut buf = [0u8; 4];
+    ///     cursor.read_exact(&mut buf).await.unwrap();
+    ///     assert_eq!(&buf, b"pong");
+    /// }
+    /// # );
+    /// ```
+    pub struct ExpectedCursor {
+        script: Script,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl ExpectedCursor {
+    pub fn new() -> Self {
+        Self {
+            script: Script { expected: VecDeque::new(), index: 0, position: 0, len: 0 },
+            _p: PhantomPinned,
+        }
+    }
+
+    fn expect(mut self, expectation: Expectation) -> Self {
+        self.script.expected.push_back(expectation);
+        self
+    }
+
+    /// Expects reads that together consume exactly `bytes`, which are handed to the reader.
+    pub fn expect_read(self, bytes: &[u8]) -> Self {
+        self.expect(Expectation::Read(bytes.to_vec()))
+    }
+
+    /// Expects writes that together deliver exactly `bytes`.
+    pub fn expect_write(self, bytes: &[u8]) -> Self {
+        self.expect(Expectation::Write(bytes.to_vec()))
+    }
+
+    pub fn expect_seek(self, pos: SeekFrom) -> Self {
+        self.expect(Expectation::Seek(pos))
+    }
+
+    pub fn expect_flush(self) -> Self {
+        self.expect(Expectation::Flush)
+    }
+
+    pub fn expect_close(self) -> Self {
+        self.expect(Expectation::Close)
+    }
+
+    /// Makes the next operation, whatever it is, fail with an error of the given kind.
+    pub fn then_error(self, kind: ErrorKind) -> Self {
+        self.expect(Expectation::Error(kind))
+    }
+
+    /// Panics if any expectations have not been met yet.
+    pub fn verify(&self) {
+        self.script.verify()
+    }
+}
+
+impl Default for ExpectedCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for ExpectedCursor {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        Poll::Ready(self.project().script.read(buf))
+    }
+}
+
+impl Write for ExpectedCursor {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Poll::Ready(self.project().script.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(self.project().script.flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(self.project().script.close())
+    }
+}
+
+impl Seek for ExpectedCursor {
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        Poll::Ready(self.project().script.seek(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_all!(ExpectedCursor: Unpin);
+        assert_impl_all!(ExpectedCursor: Read, Write, Seek);
+    }
+
+    #[test]
+    fn matching_sequence() {
+        let mut cursor = Box::pin(ExpectedCursor::new()
+            .expect_read(&[1, 2, 3, 4])
+            .expect_seek(SeekFrom::Start(0))
+            .expect_write(&[5, 6, 7])
+            .expect_flush()
+            .then_error(ErrorKind::BrokenPipe));
+        block_on(async {
+            let mut buf = [0u8; 3];
+            assert_eq!(cursor.read(&mut buf).await.unwrap(), 3);
+            assert_eq!(buf, [1, 2, 3]);
+            assert_eq!(cursor.read(&mut buf).await.unwrap(), 1);
+            assert_eq!(buf[0], 4);
+            assert_eq!(cursor.seek(SeekFrom::Start(0)).await.unwrap(), 0);
+            // A write delivered in two parts still matches.
+            assert_eq!(cursor.write(&[5]).await.unwrap(), 1);
+            assert_eq!(cursor.write(&[6, 7]).await.unwrap(), 2);
+            cursor.flush().await.unwrap();
+            assert_eq!(cursor.write(&[8]).await.unwrap_err().kind(), ErrorKind::BrokenPipe);
+        });
+        cursor.verify();
+    }
+
+    #[test]
+    #[should_panic(expected = "operation #0: expected write of [1, 2, 3], got write of [1, 9]")]
+    fn mismatched_bytes() {
+        let mut cursor = Box::pin(ExpectedCursor::new().expect_write(&[1, 2, 3]));
+        block_on(cursor.write(&[1, 9])).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "operation #1: expected flush, got read")]
+    fn out_of_order() {
+        let mut cursor = Box::pin(ExpectedCursor::new().expect_write(&[1]).expect_flush());
+        block_on(async {
+            cursor.write_all(&[1]).await.unwrap();
+            cursor.read(&mut [0u8; 1]).await.unwrap();
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "1 expectation(s) not met, starting with operation #1: seek to Start(2)")]
+    fn unmet_at_drop() {
+        let mut cursor = Box::pin(ExpectedCursor::new().expect_read(&[1]).expect_seek(SeekFrom::Start(2)));
+        block_on(cursor.read(&mut [0u8; 1])).unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+use std::pin::Pin;
+
+/// Shorthand for reborrowing a boxed `!Unpin` value as `Pin<&mut T>`.
+///
+/// ```
+/// # use async_std::io::Cursor;
+/// # use async_std::task::block_on;
+/// use pin_cursor::{PinCursor, PinMutExt};
+///
+/// let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+/// for i in 0..3u8 {
+///     block_on(cursor.pin_mut().write(&[i])).unwrap();
+/// }
+/// assert_eq!(cursor.bytes_ref(), [0, 1, 2]);
+/// ```
+pub trait PinMutExt<T: ?Sized> {
+    fn pin_mut(&mut self) -> Pin<&mut T>;
+}
+
+impl<T: ?Sized> PinMutExt<T> for Pin<Box<T>> {
+    fn pin_mut(&mut self) -> Pin<&mut T> {
+        self.as_mut()
+    }
+}
+
+impl<T: ?Sized> PinMutExt<T> for Pin<&mut T> {
+    fn pin_mut(&mut self) -> Pin<&mut T> {
+        self.as_mut()
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+use std::task::{Context, Poll};
+
+use crate::random::{FaultProfile, Rng};
+
+/// Fault injection state for one direction (reads or writes) of a cursor.
+///
+/// Every non-empty poll goes through [`poll_begin`](Faults::poll_begin), which applies
+/// the configured faults in a fixed order:
+///
+/// 0. every n-th operation, if so configured, fails with `ErrorKind::Interrupted` right away;
+/// 1. the schedule caps the size of the chunk, and so do random faults, if any;
+/// 2. if transferring that chunk would carry the cumulative byte count past the
+///    `fail_after` limit, the poll fails without transferring anything;
+/// 3. random faults may fail the poll;
+/// 4. pending stalls are returned before the chunk is finally let through,
+///    followed by random stalls.
+pub(crate) struct Faults {
+    direction: &'static str,
+    schedule: VecDeque<usize>,
+    fail_after: Option<u64>,
+    stalls: u32,
+    stalled: u32,
+    interrupt_every: Option<u64>,
+    attempts: u64,
+    transferred: u64,
+    // The value of `transferred` when the counters were last reset.
+    counted_from: u64,
+    calls: u64,
+    random: Option<(Rng, FaultProfile)>,
+}
+
+impl Faults {
+    pub(crate) fn new(direction: &'static str) -> Self {
+        Self {
+            direction,
+            schedule: VecDeque::new(),
+            fail_after: None,
+            stalls: 0,
+            stalled: 0,
+            interrupt_every: None,
+            attempts: 0,
+            transferred: 0,
+            counted_from: 0,
+            calls: 0,
+            random: None,
+        }
+    }
+
+    pub(crate) fn set_schedule(&mut self, sizes: impl IntoIterator<Item=usize>) {
+        self.schedule = sizes.into_iter().collect();
+    }
+
+    pub(crate) fn set_fail_after(&mut self, bytes: u64) {
+        self.fail_after = Some(bytes);
+    }
+
+    pub(crate) fn set_stalls(&mut self, polls: u32) {
+        self.stalls = polls;
+    }
+
+    pub(crate) fn set_interrupt_every(&mut self, n: u64) {
+        assert!(n > 0, "cannot interrupt every 0th operation");
+        self.interrupt_every = Some(n);
+    }
+
+    pub(crate) fn set_random(&mut self, seed: u64, profile: FaultProfile) {
+        self.random = Some((Rng::new(seed), profile));
+    }
+
+    /// The number of bytes transferred since the counters were last reset.
+    pub(crate) fn transferred(&self) -> u64 {
+        self.transferred - self.counted_from
+    }
+
+    /// The number of completed operations that transferred at least one byte.
+    pub(crate) fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Forgets the stalls of an operation that will not be polled again.
+    pub(crate) fn abandon(&mut self) {
+        self.stalled = 0;
+    }
+
+    /// Zeroes the counters. The `fail_after` limit still counts from the very first operation.
+    pub(crate) fn reset_counters(&mut self) {
+        self.counted_from = self.transferred;
+        self.calls = 0;
+    }
+
+    /// Decides how many of the `len` bytes that could be transferred right now may actually be.
+    pub(crate) fn poll_begin(&mut self, cx: &mut Context<'_>, len: usize) -> Poll<Result<usize>> {
+        // Polls repeated after a scheduled stall belong to an operation that was already counted.
+        if self.stalled == 0 {
+            if let Some(n) = self.interrupt_every {
+                self.attempts += 1;
+                if self.attempts % n == 0 {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Interrupted, format!("injected {} interruption", self.direction))));
+                }
+            }
+        }
+        let mut chunk = match self.schedule.front() {
+            Some(&limit) => len.min(limit),
+            None => len,
+        };
+        if let Some((rng, profile)) = &mut self.random {
+            if profile.max_chunk > 0 {
+                chunk = chunk.min(rng.up_to(profile.max_chunk));
+            }
+        }
+        if let Some(limit) = self.fail_after {
+            if self.transferred + chunk as u64 > limit {
+                return Poll::Ready(Err(Error::other(format!("injected {} failure after {} bytes", self.direction, limit))));
+            }
+        }
+        if let Some((rng, profile)) = &mut self.random {
+            if rng.chance(profile.error_probability) {
+                return Poll::Ready(Err(Error::other(format!("injected random {} failure", self.direction))));
+            }
+        }
+        if self.stalled < self.stalls {
+            self.stalled += 1;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if let Some((rng, profile)) = &mut self.random {
+            if rng.chance(profile.stall_probability) {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(Ok(chunk))
+    }
+
+    /// Records the completion of an operation that transferred `n` bytes.
+    pub(crate) fn finish(&mut self, n: usize) {
+        self.schedule.pop_front();
+        self.stalled = 0;
+        self.transferred += n as u64;
+        if n > 0 {
+            self.calls += 1;
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::future::Future;
+use std::io::{Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor, ops};
+
+// The named futures only hold a pinned reference and a buffer, so they are `Unpin`.
+// Dropping a read or write future resets the stalls of the operation, see "Cancellation"
+// in the crate docs.
+// `Pinned` makes them `!Unpin`, to check that consumers pin the futures they poll.
+
+/// Future returned by [`PinCursor::read`].
+pub struct ReadFuture<'a, 'b, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) buf: &'b mut [u8],
+}
+
+impl<T> Future for ReadFuture<'_, '_, T>
+    where T: AsRef<[u8]>
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.cursor.as_mut().poll_read(cx, this.buf)
+    }
+}
+
+impl<T> Drop for ReadFuture<'_, '_, T> {
+    fn drop(&mut self) {
+        self.cursor.as_mut().abandon_read();
+    }
+}
+
+/// Future returned by [`PinCursor::write`].
+pub struct WriteFuture<'a, 'b, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) buf: &'b [u8],
+}
+
+impl<T> Future for WriteFuture<'_, '_, T>
+    where T: Buffer
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.cursor.as_mut().poll_write(cx, this.buf)
+    }
+}
+
+impl<T> Drop for WriteFuture<'_, '_, T> {
+    fn drop(&mut self) {
+        self.cursor.as_mut().abandon_write();
+    }
+}
+
+/// Future returned by [`PinCursor::seek`].
+pub struct SeekFuture<'a, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) pos: SeekFrom,
+}
+
+impl<T> Future for SeekFuture<'_, T>
+    where T: AsRef<[u8]>
+{
+    type Output = Result<u64>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.cursor.as_mut().poll_seek(cx, this.pos)
+    }
+}
+
+/// Future returned by [`PinCursor::read_until`].
+pub struct ReadUntilFuture<'a, 'b, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) delim: u8,
+    pub(crate) buf: &'b mut Vec<u8>,
+    pub(crate) read: usize,
+}
+
+impl<T> Future for ReadUntilFuture<'_, '_, T>
+    where T: AsRef<[u8]>
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        ops::poll_read_until(this.cursor.as_mut(), cx, this.delim, this.buf, &mut this.read)
+    }
+}
+
+impl<T> Drop for ReadUntilFuture<'_, '_, T> {
+    fn drop(&mut self) {
+        self.cursor.as_mut().abandon_read();
+    }
+}
+
+/// Future returned by [`PinCursor::read_line`].
+pub struct ReadLineFuture<'a, 'b, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) buf: &'b mut String,
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) read: usize,
+}
+
+impl<T> Future for ReadLineFuture<'_, '_, T>
+    where T: AsRef<[u8]>
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        ops::poll_read_line(this.cursor.as_mut(), cx, this.buf, &mut this.bytes, &mut this.read)
+    }
+}
+
+impl<T> Drop for ReadLineFuture<'_, '_, T> {
+    fn drop(&mut self) {
+        self.cursor.as_mut().abandon_read();
+    }
+}
+
+/// Future returned by [`PinCursor::collect_all`].
+pub struct CollectAllFuture<'a, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) buf: Vec<u8>,
+    pub(crate) filled: usize,
+}
+
+impl<T> Future for CollectAllFuture<'_, T>
+    where T: AsRef<[u8]>
+{
+    type Output = Result<Vec<u8>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        ops::poll_collect_all(this.cursor.as_mut(), cx, &mut this.buf, &mut this.filled)
+    }
+}
+
+impl<T> Drop for CollectAllFuture<'_, T> {
+    fn drop(&mut self) {
+        self.cursor.as_mut().abandon_read();
+    }
+}
+
+/// Future returned by [`PinCursor::read_uninit`].
+pub struct ReadUninitFuture<'a, 'b, T> {
+    pub(crate) cursor: Pin<&'a mut PinCursor<T>>,
+    pub(crate) buf: Option<&'b mut [MaybeUninit<u8>]>,
+}
+
+impl<'b, T> Future for ReadUninitFuture<'_, 'b, T>
+    where T: AsRef<[u8]>
+{
+    type Output = Result<&'b mut [u8]>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.cursor.as_mut().project().affinity.check("poll_read");
+        let len = this.buf.as_ref().map_or(0, |buf| buf.len());
+        let src = ready!(this.cursor.as_mut().poll_read_slice(cx, len))?;
+        let prefix = &mut this.buf.take().expect("polled after completion")[..src.len()];
+        for (dst, &byte) in prefix.iter_mut().zip(src) {
+            dst.write(byte);
+        }
+        // SAFETY: every byte of `prefix` has just been written, and MaybeUninit<u8> has the layout of u8.
+        Poll::Ready(Ok(unsafe { &mut *(prefix as *mut [MaybeUninit<u8>] as *mut [u8]) }))
+    }
+}
+
+impl<T> Drop for ReadUninitFuture<'_, '_, T> {
+    fn drop(&mut self) {
+        self.cursor.as_mut().abandon_read();
+    }
+}
+
+pin_project! {
+    /// A `!Unpin` wrapper around a future, returned by [`PinCursor::read_pinned`] and its siblings.
+    pub struct Pinned<F> {
+        #[pin]
+        inner: F,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<F> Pinned<F> {
+    /// Makes `inner` `!Unpin`; this works for any future, such as [`ReadLineFuture`].
+    pub fn new(inner: F) -> Self {
+        Self { inner, _p: PhantomPinned }
+    }
+}
+
+impl<F> Future for Pinned<F>
+    where F: Future
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
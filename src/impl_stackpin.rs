@@ -1,19 +1,204 @@
-use async_std::io::{Cursor, Read, Seek, Write};
+use async_std::io::{Cursor, Read, Write};
 use stackpin::FromUnpinned;
 
-use crate::PinCursor;
+#[cfg(feature = "stream")]
+use crate::{ObservableCursor, PinStream};
+#[cfg(feature = "sink")]
+use crate::PinSink;
+#[cfg(feature = "throttle")]
+use crate::ThrottledCursor;
+use crate::{
+    ChainCursor, EchoCursor, ExpectedCursor, LimitCursor, MockSocket, NullCursor, PinBufReader, PinBufWriter,
+    PinCursor, PinDuplex, PinIo, PinPipeReader, PinPipeWriter, PinReader, PinSharedCursor, PinWriter, ReadHalf,
+    RepeatCursor, RingCursor, SparseCursor, TeeCursor, TransactionalCursor, WindowCursor, WriteHalf, ZeroCursor,
+};
 
-unsafe impl<T> FromUnpinned<Cursor<T>> for PinCursor<T>
-    where T: Unpin,
-          Cursor<T>: Write + Read + Seek
+// Implements `FromUnpinned<$src>` for `$ty`, building it with `$wrap`, which must not
+// depend on the address of the result. Without a source, a value of `$ty` itself is taken:
+// the types of this crate may be moved freely until they are pinned, which allows them
+// to be configured before they are put on the stack. Every `!Unpin` type should be listed below.
+macro_rules! from_unpinned {
+    ($(#[$attr:meta])* [$($gen:tt)*] $src:ty => $ty:ty, $wrap:expr) => {
+        $(#[$attr])*
+        unsafe impl<$($gen)*> FromUnpinned<$src> for $ty {
+            type PinData = ();
+
+            unsafe fn from_unpinned(src: $src) -> (Self, Self::PinData) {
+                (($wrap)(src), ())
+            }
+
+            unsafe fn on_pin(&mut self, _pin_data: Self::PinData) {
+                // do nothing
+            }
+        }
+    };
+    ($(#[$attr:meta])* [$($gen:tt)*] $ty:ty) => {
+        from_unpinned!($(#[$attr])* [$($gen)*] $ty => $ty, |src| src);
+    };
+}
+
+/// A buffer that a [`PinCursor`] can be pinned on the stack from directly, without
+/// wrapping it in a `Cursor` first.
+///
+/// This is a separate trait, rather than a blanket impl, so that pinning from a `Cursor`
+/// keeps inferring the buffer type.
+pub trait CursorBacking {}
+
+impl CursorBacking for Vec<u8> {}
+impl CursorBacking for &mut Vec<u8> {}
+impl CursorBacking for &mut [u8] {}
+impl CursorBacking for &[u8] {}
+impl CursorBacking for String {}
+impl<const N: usize> CursorBacking for [u8; N] {}
+#[cfg(feature = "bytes")]
+impl CursorBacking for bytes::BytesMut {}
+#[cfg(feature = "bytes")]
+impl CursorBacking for bytes::Bytes {}
+
+from_unpinned!([T] Cursor<T> => PinCursor<T>, PinCursor::wrap);
+from_unpinned!([T: CursorBacking] T => PinCursor<T>, |src| PinCursor::wrap(Cursor::new(src)));
+from_unpinned!([T] PinCursor<T>);
+
+/// Starts the cursor at the given position, as a model for `!Unpin` types that need some setup
+/// once they are in place: `from_unpinned` passes the position on as `PinData`, and `on_pin`,
+/// which runs after the cursor has been pinned, applies it.
+unsafe impl<T> FromUnpinned<(T, u64)> for PinCursor<T>
+    where T: CursorBacking
 {
-    type PinData = ();
+    type PinData = u64;
+
+    unsafe fn from_unpinned((data, pos): (T, u64)) -> (Self, Self::PinData) {
+        (PinCursor::wrap(Cursor::new(data)), pos)
+    }
+
+    unsafe fn on_pin(&mut self, pos: Self::PinData) {
+        self.c.set_position(pos);
+    }
+}
+
+from_unpinned!([R: Read + Unpin] R => PinReader<R>, PinReader::wrap);
+from_unpinned!([W: Write + Unpin] W => PinWriter<W>, PinWriter::wrap);
+from_unpinned!([T: Unpin] T => PinIo<T>, PinIo::wrap);
+from_unpinned!([R: Read] R => PinBufReader<R>, PinBufReader::new);
+from_unpinned!([W: Write] W => PinBufWriter<W>, PinBufWriter::new);
+from_unpinned!(#[cfg(feature = "stream")] [S: futures_core::Stream + Unpin] S => PinStream<S>, PinStream::wrap);
+from_unpinned!(#[cfg(feature = "sink")] [S: Unpin] S => PinSink<S>, PinSink::wrap);
+
+from_unpinned!([T] ChainCursor<T>);
+from_unpinned!([] EchoCursor);
+from_unpinned!([] ExpectedCursor);
+from_unpinned!([T] LimitCursor<T>);
+from_unpinned!([] MockSocket);
+from_unpinned!([] NullCursor);
+from_unpinned!(#[cfg(feature = "stream")] [T] ObservableCursor<T>);
+from_unpinned!([] PinDuplex);
+from_unpinned!([] PinPipeReader);
+from_unpinned!([] PinPipeWriter);
+from_unpinned!([] PinSharedCursor);
+from_unpinned!([T] ReadHalf<T>);
+from_unpinned!([] RepeatCursor);
+from_unpinned!([] RingCursor);
+from_unpinned!([] SparseCursor);
+from_unpinned!([T] TeeCursor<T>);
+from_unpinned!(#[cfg(feature = "throttle")] [I] ThrottledCursor<I>);
+from_unpinned!([T] TransactionalCursor<T>);
+from_unpinned!([T] WindowCursor<T>);
+from_unpinned!([T] WriteHalf<T>);
+from_unpinned!([] ZeroCursor);
+
+#[cfg(test)]
+mod tests {
+    use std::io::SeekFrom;
+
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use stackpin::stack_let;
+
+    use crate::pin_duplex;
+
+    use super::*;
+
+    #[test]
+    fn from_raw_buffers() {
+        stack_let!(mut cursor : PinCursor<_> = vec![1u8, 2]);
+        let mut buf = [0u8; 3];
+        block_on(async {
+            cursor.as_mut().seek(SeekFrom::End(0)).await.unwrap();
+            cursor.as_mut().write_all(&[3]).await.unwrap();
+            cursor.as_mut().set_position(0);
+            cursor.as_mut().read_exact(&mut buf).await.unwrap();
+        });
+        assert_eq!(buf, [1, 2, 3]);
+        assert_eq!(cursor.as_mut().take_buffer(), [1, 2, 3]);
+
+        let mut data = [0u8; 4];
+        {
+            stack_let!(mut cursor : PinCursor<_> = &mut data[..]);
+            block_on(cursor.as_mut().write_all(b"abcd")).unwrap();
+        }
+        assert_eq!(&data, b"abcd");
+
+        stack_let!(mut cursor : PinCursor<_> = String::from("text"));
+        let mut line = String::new();
+        block_on(cursor.as_mut().read_line(&mut line)).unwrap();
+        assert_eq!(line, "text");
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn from_bytes() {
+        stack_let!(mut cursor : PinCursor<_> = bytes::BytesMut::from(&b"ab"[..]));
+        cursor.as_mut().set_position(2);
+        block_on(cursor.as_mut().write_all(b"cd")).unwrap();
+        assert_eq!(&cursor.as_mut().freeze()[..], b"abcd");
+    }
+
+    #[test]
+    fn initial_position() {
+        stack_let!(mut cursor : PinCursor<_> = (b"abcdef".to_vec(), 2));
+        assert_eq!(cursor.position(), 2);
+        let mut buf = [0u8; 3];
+        block_on(cursor.as_mut().read_exact(&mut buf)).unwrap();
+        assert_eq!(&buf, b"cde");
+    }
+
+    #[test]
+    fn configured_cursor() {
+        stack_let!(mut cursor : PinCursor<_> = PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])).with_read_schedule(vec![1]));
+        let mut buf = [0u8; 3];
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 1);
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 2);
+    }
+
+    #[test]
+    fn other_wrappers() {
+        stack_let!(mut reader : PinReader<_> = Cursor::new(b"line\nrest".to_vec()));
+        let mut line = String::new();
+        block_on(reader.as_mut().read_line(&mut line)).unwrap();
+        assert_eq!(line, "line\n");
 
-    unsafe fn from_unpinned(src: Cursor<T>) -> (Self, Self::PinData) {
-        (PinCursor::wrap(src), ())
+        let (a, b) = pin_duplex();
+        stack_let!(mut a : PinDuplex = a);
+        stack_let!(mut b : PinDuplex = b);
+        let mut buf = [0u8; 4];
+        block_on(async {
+            a.as_mut().write_all(b"ping").await.unwrap();
+            b.as_mut().read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"ping");
+            b.as_mut().write_all(b"pong").await.unwrap();
+            a.as_mut().read_exact(&mut buf).await.unwrap();
+        });
+        assert_eq!(&buf, b"pong");
     }
 
-    unsafe fn on_pin(&mut self, _pin_data: Self::PinData) {
-        // do nothing
+    #[test]
+    fn from_cursor() {
+        let mut data = vec![1u8, 2];
+        {
+            stack_let!(mut cursor : PinCursor<_> = Cursor::new(&mut data));
+            cursor.as_mut().set_position(2);
+            block_on(cursor.as_mut().write_all(&[3])).unwrap();
+        }
+        assert_eq!(data, [1, 2, 3]);
     }
 }
@@ -0,0 +1,209 @@
+use std::future::{Future, poll_fn};
+use std::io::{IoSlice, IoSliceMut, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{BufRead, Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::ops;
+
+pin_project! {
+    /// A `!Unpin` wrapper around any `Unpin` I/O object.
+    ///
+    /// Each of `Read`, `Write`, `Seek` and `BufRead` is implemented exactly when
+    /// the wrapped type implements it, so one wrapper covers fake sockets, fake files
+    /// and anything in between.
+    pub struct PinIo<T> {
+        io: T,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> PinIo<T>
+    where T: Unpin
+{
+    pub fn wrap(io: T) -> Self {
+        Self { io, _p: PhantomPinned }
+    }
+
+    pub fn unwrap(self) -> T {
+        self.io
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    pub fn get_mut(self: Pin<&mut Self>) -> &mut T {
+        self.project().io
+    }
+}
+
+impl<T> PinIo<T>
+    where T: Read + Unpin
+{
+    pub fn read<'a, 'b>(mut self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> impl Future<Output=Result<usize>> + use<'a, 'b, T> {
+        poll_fn(move |cx| self.as_mut().poll_read(cx, buf))
+    }
+}
+
+impl<T> PinIo<T>
+    where T: Write + Unpin
+{
+    pub fn write<'a, 'b>(mut self: Pin<&'a mut Self>, buf: &'b [u8]) -> impl Future<Output=Result<usize>> + use<'a, 'b, T> {
+        poll_fn(move |cx| self.as_mut().poll_write(cx, buf))
+    }
+}
+
+impl<T> PinIo<T>
+    where T: Seek + Unpin
+{
+    pub fn seek(mut self: Pin<&mut Self>, pos: SeekFrom) -> impl Future<Output=Result<u64>> + '_ {
+        poll_fn(move |cx| self.as_mut().poll_seek(cx, pos))
+    }
+}
+
+impl<T> Read for PinIo<T>
+    where T: Read + Unpin
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        Pin::new(self.project().io).poll_read(cx, buf)
+    }
+
+    fn poll_read_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
+        Pin::new(self.project().io).poll_read_vectored(cx, bufs)
+    }
+}
+
+impl<T> PinIo<T>
+    where T: BufRead + Unpin
+{
+    /// Reads up to and including `delim`, or up to EOF, and appends the bytes to `buf`.
+    ///
+    /// Returns the number of bytes read, which is 0 at EOF.
+    pub fn read_until<'a, 'b>(self: Pin<&'a mut Self>, delim: u8, buf: &'b mut Vec<u8>) -> impl Future<Output=Result<usize>> + use<'a, 'b, T> {
+        ops::read_until(self, delim, buf)
+    }
+
+    /// Reads a line, including the trailing `\n` if there is one, and appends it to `buf`.
+    ///
+    /// Fails with `ErrorKind::InvalidData` if the line is not valid UTF-8.
+    pub fn read_line<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut String) -> impl Future<Output=Result<usize>> + use<'a, 'b, T> {
+        ops::read_line(self, buf)
+    }
+}
+
+impl<T> BufRead for PinIo<T>
+    where T: BufRead + Unpin
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        Pin::new(self.project().io).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        Pin::new(self.project().io).consume(amt)
+    }
+}
+
+impl<T> Write for PinIo<T>
+    where T: Write + Unpin
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Pin::new(self.project().io).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<Result<usize>> {
+        Pin::new(self.project().io).poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(self.project().io).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(self.project().io).poll_close(cx)
+    }
+}
+
+impl<T> Seek for PinIo<T>
+    where T: Seek + Unpin
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        Pin::new(self.project().io).poll_seek(cx, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use async_std::io::Cursor;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    /// A fake socket: bytes written to it come back out of reads. It cannot seek.
+    #[derive(Default)]
+    struct Loopback(VecDeque<u8>);
+
+    impl Read for Loopback {
+        fn poll_read(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+            let n = buf.len().min(self.0.len());
+            for (dst, src) in buf.iter_mut().zip(self.0.drain(..n)) {
+                *dst = src;
+            }
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    impl Write for Loopback {
+        fn poll_write(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+            self.0.extend(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(PinIo<Loopback>: Unpin, Seek, BufRead);
+        assert_impl_all!(PinIo<Loopback>: Read, Write);
+        assert_not_impl_any!(PinIo<Cursor<Vec<u8>>>: Unpin);
+        assert_impl_all!(PinIo<Cursor<Vec<u8>>>: Read, Write, Seek, BufRead);
+    }
+
+    #[test]
+    fn read_write_without_seek() {
+        let mut io = Box::pin(PinIo::wrap(Loopback::default()));
+        let mut buf = [0u8; 4];
+        block_on(async {
+            assert_eq!(io.as_mut().write(&[1, 2, 3]).await.unwrap(), 3);
+            assert_eq!(io.as_mut().read(&mut buf).await.unwrap(), 3);
+        });
+        assert_eq!(buf[..3], [1, 2, 3]);
+        assert!(io.get_ref().0.is_empty());
+    }
+
+    #[test]
+    fn full_cursor() {
+        let mut io = Box::pin(PinIo::wrap(Cursor::new(Vec::new())));
+        let mut buf = [0u8; 2];
+        block_on(async {
+            io.as_mut().write(&[1, 2, 3]).await.unwrap();
+            assert_eq!(io.as_mut().seek(SeekFrom::Start(1)).await.unwrap(), 1);
+            assert_eq!(io.as_mut().read(&mut buf).await.unwrap(), 2);
+        });
+        assert_eq!(buf, [2, 3]);
+    }
+}
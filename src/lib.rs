@@ -5,8 +5,10 @@
 //! where you want to ensure that your own asynchronous IO code behaves correctly when reading from
 //! or writing to something that is *definitely* `!Unpin`.
 //!
-//! - It can be backed by any `Unpin` data buffer that can be slotted into `async_std::io::Cursor`.
-//!   Usually `Vec<u8>` or `&mut [u8]` (e. g. from an array) are used.
+//! - It can be backed by any data buffer that implements [`Buffer`], `Unpin` or not.
+//!   Usually `Vec<u8>` or `&mut [u8]` (e. g. from an array) are used; owned `[u8; N]` arrays work too.
+//!   Read-only buffers such as `&[u8]` only need `AsRef<[u8]>`, and give a cursor that can be
+//!   read and seeked but not written.
 //! - It implements `async_std::io::{Read, Write, Seek}`, so you can poll these traits' methods
 //!   in your own futures.
 //! - At the same time, it provides several high-level methods through which you can manipulate
@@ -54,6 +56,92 @@
 //! # );
 //! ```
 //!
+//! # Backing buffers
+//!
+//! Each capability of a cursor needs only its own bound on the buffer: reading, seeking and
+//! the read-side helpers need `AsRef<[u8]>`, writing needs [`Buffer`], and constructing
+//! and unwrapping need nothing at all. So for common buffers:
+//!
+//! | Buffer         | `Read`, `BufRead`, `Seek` | `Write`                 |
+//! |----------------|---------------------------|-------------------------|
+//! | `Vec<u8>`      | yes                       | yes, growing            |
+//! | `&mut Vec<u8>` | yes                       | yes, growing            |
+//! | `&mut [u8]`    | yes                       | yes, up to its end      |
+//! | `&[u8]`        | yes                       | no                      |
+//! | `Box<[u8]>`    | yes                       | no                      |
+//! | `BytesMut`     | yes                       | yes, growing            |
+//! | `Bytes`        | yes                       | no                      |
+//!
+//! `BytesMut` and `Bytes` need the `bytes` feature.
+//!
+//! The buffer does not have to be `Unpin`, but it is not structurally pinned either: the cursor
+//! only ever gives out `&T` and `&mut T`, never `Pin<&mut T>`, and methods such as
+//! [`replace_buffer`](PinCursor::replace_buffer), [`take_buffer`](PinCursor::take_buffer) and
+//! [`unwrap`](PinCursor::unwrap) move it. So a `!Unpin` buffer is fine as long as it does not
+//! depend on its own address; a self-referential one should keep the parts that do behind
+//! a `Pin<Box<_>>` of its own.
+//!
+//! # Fault injection
+//!
+//! Before pinning, a `PinCursor` can be configured to misbehave in controlled ways:
+//! short reads and writes following a schedule, failures after a number of bytes,
+//! spurious `Poll::Pending` stalls, and reproducible random mixes of all three.
+//! See the builder methods such as [`with_read_schedule`](PinCursor::with_read_schedule)
+//! for details and the order in which combined modes apply.
+//!
+//! # Cancellation
+//!
+//! A single poll of a `PinCursor` that returns `Poll::Pending` has not transferred anything,
+//! so the operations that complete in one transfer, such as [`read`](PinCursor::read),
+//! [`write`](PinCursor::write) and [`seek`](PinCursor::seek), are cancel-safe: dropping their
+//! futures before completion leaves the buffer, the position and the counters as they were.
+//! As for the fault injection state:
+//!
+//! - schedule entries are only used up by completed operations, so the operation after
+//!   a dropped one gets the entry that the dropped one would have used;
+//! - stalls start over: when one of this crate's futures is dropped, the stalls it went through
+//!   are forgotten, and the next operation stalls the full number of times;
+//! - random faults draw from their sequence on every poll, so a dropped operation still
+//!   shifts the faults that come after it;
+//! - byte limits like [`fail_read_after`](PinCursor::fail_read_after) count transferred bytes only.
+//!
+//! Operations that take several transfers may return `Poll::Pending` after some of them,
+//! and keep what they transferred before being dropped: the position has moved past those bytes.
+//! [`read_until`](PinCursor::read_until) has already appended them to its buffer, so calling it
+//! again picks up where the dropped one left off. [`read_line`](PinCursor::read_line) and
+//! [`collect_all`](PinCursor::collect_all) are not cancel-safe: `read_line` only appends whole
+//! lines, and `collect_all` only hands out its data on completion, so the bytes that a dropped
+//! one has taken are lost.
+//! Futures from the async-std extension traits, such as `read_exact`, poll the cursor directly
+//! and do not tell it when they are dropped, so after dropping one of them mid-stall,
+//! the next operation only goes through the remaining stalls.
+//!
+//! # Other wrappers
+//!
+//! [`PinReader`] and [`PinWriter`] give the same `!Unpin` guarantee to any `Unpin` reader or writer,
+//! and [`PinIo`] covers objects that do several of these things at once.
+//! [`PinBufReader`] and [`PinBufWriter`] add buffers whose state can be inspected.
+//! [`MockSocket`] plays back scripted incoming bytes and records outgoing ones.
+//! [`pin_duplex`] creates a connected pair of in-memory endpoints for protocol tests,
+//! and [`pin_pipe`] a bounded one-way pipe for backpressure tests.
+//! [`RingCursor`] is a FIFO where reads consume what writes append, and [`EchoCursor`]
+//! is a loopback with optional backpressure for full-duplex code talking to itself.
+//! [`ChainCursor`] reads several cursors in sequence, each with its own faults.
+//! [`LimitCursor`] cuts a cursor short, [`WindowCursor`] confines it to a byte range,
+//! and [`TeeCursor`] keeps a transcript of all writes.
+//! [`TransactionalCursor`] stages writes until they are committed or rolled back.
+//! [`ZeroCursor`] and [`NullCursor`] are an endless source of zeroes and a bottomless sink,
+//! and [`RepeatCursor`] endlessly repeats a pattern.
+//! [`SparseCursor`] stores only the bytes written to it, so it can have huge holes.
+//! [`PinCursor::shared`] pairs a cursor with an [`Observer`] that can watch its buffer from another task,
+//! and a [`MultiCursorSet`], created with [`MultiCursor::new`], hands out any number of cursors over one buffer.
+//! [`UnpinCursor`] is an `Unpin` twin of `PinCursor`, for checking that a bug is really about pinning.
+//!
+//! # Strict mocks
+//!
+//! When a permissive buffer is not enough, [`ExpectedCursor`] accepts only an exact,
+//! pre-recorded sequence of reads, writes, seeks and flushes, and panics on anything else.
+//!
 //! # Features
 //!
 //! The optional feature `stackpin` enables integration with [stackpin], a crate that provides
@@ -71,115 +159,2879 @@
 //! ```
 //!
 //! Now you have a correctly pinned `PinCursor` that's allocated on stack instead of in a box.
+//! It cannot be moved out of its slot again, so either read the data back through the `&mut`
+//! borrow once the cursor is gone, or take it out with [`PinCursor::take_buffer`].
+//!
+//! For the common buffers, which implement `CursorBacking`, the `Cursor` can be left out:
+//! `stack_let!(mut cursor : PinCursor<_> = data)` starts at the beginning of `data`, and
+//! `stack_let!(mut cursor : PinCursor<_> = (data, 2))` at position 2. The latter passes the
+//! position as `FromUnpinned::PinData` and applies it in `on_pin`, once the cursor is in place;
+//! `!Unpin` types of your own that need such setup can do the same.
+//! A cursor built and configured beforehand can be put on the stack as it is.
+//!
+//! The other `!Unpin` types of this crate can be stack-pinned as well: [`PinReader`] and
+//! the other wrappers straight from what they wrap, the rest from a value made in advance,
+//! such as one end of a [`pin_duplex`] pair.
+//!
+//! The optional features `stream` and `sink` add [`PinStream`] and [`PinSink`], which do the same
+//! for `futures_core::Stream` and `futures_sink::Sink`. The `stream` feature also enables
+//! [`PinCursor::trace`], a stream of bytes paired with the positions they were read from,
+//! and [`ObservableCursor`], which broadcasts its writes to subscribed streams.
+//!
+//! The optional feature `log` makes every cursor emit a `trace!` record for each completed
+//! read, write, seek, flush and close, with its position and byte count, under the target
+//! `"pin_cursor"`.
+//!
+//! The optional feature `bytes` lets cursors be backed by `bytes::BytesMut`, which writes
+//! grow, and by read-only `bytes::Bytes`. [`PinCursor::freeze`] takes the written data out.
+//!
+//! The optional feature `throttle` adds [`ThrottledCursor`], which limits throughput
+//! to a number of bytes per second using async-std timers.
 //!
 //! [stackpin]: https://docs.rs/stackpin/0.0.2
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
 use std::future::Future;
-use std::io::{IoSlice, IoSliceMut, Result, SeekFrom};
+use std::io::{Error, ErrorKind, IoSlice, IoSliceMut, Result, SeekFrom};
 use std::marker::PhantomPinned;
+use std::mem::MaybeUninit;
+use std::ops::Range;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker, ready};
+use std::time::Duration;
 
 use async_std::io::Cursor;
 use async_std::io::prelude::*;
 use pin_project_lite::pin_project;
 
+pub use buf_reader::PinBufReader;
+pub use buf_writer::PinBufWriter;
+pub use buffer::Buffer;
+pub use chain::ChainCursor;
+pub use close::ReadsAfterClose;
+pub use duplex::{PinDuplex, pin_duplex};
+pub use echo::EchoCursor;
+pub use expect::ExpectedCursor;
+pub use ext::PinMutExt;
+pub use future::{CollectAllFuture, Pinned, ReadFuture, ReadLineFuture, ReadUninitFuture, ReadUntilFuture, SeekFuture, WriteFuture};
+#[cfg(feature = "stackpin")]
+pub use impl_stackpin::CursorBacking;
+pub use io::PinIo;
+pub use limit::LimitCursor;
+pub use null::NullCursor;
+#[cfg(feature = "stream")]
+pub use observe::{ObservableCursor, Subscription, WriteEvent};
+pub use op::Op;
+pub use pipe::{PinPipeReader, PinPipeWriter, pin_pipe};
+pub use position::{GapPolicy, PositionPolicy};
+pub use random::FaultProfile;
+pub use reader::PinReader;
+pub use repeat::RepeatCursor;
+pub use ring::RingCursor;
+pub use shared::{MultiCursor, MultiCursorSet, Observer, PinSharedCursor};
+#[cfg(feature = "sink")]
+pub use sink::PinSink;
+pub use socket::MockSocket;
+pub use sparse::SparseCursor;
+pub use split::{ReadHalf, UnsplitError, WriteHalf};
+pub use stats::Stats;
+#[cfg(feature = "stream")]
+pub use stream::PinStream;
+pub use tee::TeeCursor;
+#[cfg(feature = "throttle")]
+pub use throttle::ThrottledCursor;
+pub use transaction::{TransactionalCursor, Visibility};
+pub use verify::verify_roundtrip;
+pub use window::WindowCursor;
+pub use writer::PinWriter;
+pub use zero::ZeroCursor;
+
+use crate::affinity::ThreadAffinity;
+use crate::clock::VirtualClock;
+use crate::close::CloseState;
+use crate::faults::Faults;
+use crate::op::OpLog;
+
+mod affinity;
+mod buf_reader;
+mod buf_writer;
+mod buffer;
+mod chain;
+mod clock;
+mod close;
+mod duplex;
+mod echo;
+mod expect;
+mod ext;
+mod faults;
+mod future;
 #[cfg(feature = "stackpin")]
 mod impl_stackpin;
+mod io;
+mod limit;
+mod null;
+#[cfg(feature = "stream")]
+mod observe;
+mod op;
+mod ops;
+mod pipe;
+mod position;
+mod random;
+mod reader;
+mod repeat;
+mod replay;
+mod ring;
+mod shared;
+#[cfg(feature = "sink")]
+mod sink;
+mod socket;
+mod sparse;
+mod split;
+mod stats;
+#[cfg(feature = "stream")]
+mod stream;
+mod tee;
+#[cfg(feature = "throttle")]
+mod throttle;
+mod transaction;
+mod verify;
+mod window;
+mod writer;
+mod zero;
+
+/// How many times [`PinCursor::spin_read`] polls before giving up.
+pub const SPIN_LIMIT: u32 = 10_000;
 
 pin_project! {
-    pub struct PinCursor<T> {
+    pub struct PinCursor<T, P = PhantomPinned> {
         c: Cursor<T>,
+        read_faults: Faults,
+        write_faults: Faults,
+        affinity: ThreadAffinity,
+        read_fill: Option<u8>,
+        loopback: bool,
+        null_sink: bool,
+        sticky_eof: bool,
+        eof_seen: bool,
+        char_aligned: bool,
+        exact_growth: bool,
+        close: CloseState,
+        error_on_full: bool,
+        strict_capacity: Option<u64>,
+        pending: Option<Vec<(u64, Vec<u8>)>>,
+        zero_write_limit: Option<u32>,
+        zero_writes: u32,
+        log: OpLog,
+        clamping_seeks: bool,
+        policy: PositionPolicy,
+        gap_policy: GapPolicy,
+        marks: HashMap<&'static str, u64>,
+        clock: VirtualClock,
         #[pin]
-        _p: PhantomPinned
+        _p: P
     }
 }
 
-impl<T> PinCursor<T>
-    where T: Unpin,
-          Cursor<T>: Write + Read + Seek
-{
+/// A cursor that is just like a [`PinCursor`], except that it is `Unpin`, for checking that
+/// generic code behaves the same whether or not its IO object is.
+///
+/// Create one with [`PinCursor::into_unpin`], after configuring the cursor as usual; the
+/// constructors, such as [`from_array`](PinCursor::from_array), only build pinned cursors,
+/// so that calls to them need no type annotations.
+/// It has the same features and implements the same traits. The methods that take
+/// `self: Pin<&mut Self>` on a `PinCursor` take `&mut self` here. The methods that return
+/// futures are left out, as the async-std extension traits provide them.
+pub type UnpinCursor<T> = PinCursor<T, ()>;
+
+impl<T> PinCursor<T> {
+    /// Wraps any cursor; this cannot fail and puts no bounds on `T`.
+    ///
+    /// Which operations the result supports depends only on the buffer: see
+    /// [Backing buffers](crate#backing-buffers).
     pub fn wrap(c: Cursor<T>) -> Self {
-        Self { c, _p: PhantomPinned }
+        Self::with_marker(c)
+    }
+
+    /// Same as [`wrap`](PinCursor::wrap), for generic helpers that are written against
+    /// fallible constructors. It succeeds for any `T`; the error type is `Infallible`.
+    pub fn try_wrap(c: Cursor<T>) -> std::result::Result<Self, Infallible> {
+        Ok(Self::wrap(c))
+    }
+
+    /// Turns this cursor into an otherwise identical [`UnpinCursor`], keeping its buffer,
+    /// position, configuration and counters.
+    pub fn into_unpin(self) -> UnpinCursor<T> {
+        self.remark(())
+    }
+}
+
+impl<T, P> PinCursor<T, P>
+    where P: Default
+{
+    fn with_marker(c: Cursor<T>) -> Self {
+        Self {
+            c,
+            read_faults: Faults::new("read"),
+            write_faults: Faults::new("write"),
+            affinity: ThreadAffinity::default(),
+            read_fill: None,
+            loopback: false,
+            null_sink: false,
+            sticky_eof: false,
+            eof_seen: false,
+            char_aligned: false,
+            exact_growth: false,
+            close: CloseState::default(),
+            error_on_full: false,
+            strict_capacity: None,
+            pending: None,
+            zero_write_limit: None,
+            zero_writes: 0,
+            log: OpLog::default(),
+            clamping_seeks: false,
+            policy: PositionPolicy::Lenient,
+            gap_policy: GapPolicy::ZeroFill,
+            marks: HashMap::new(),
+            clock: VirtualClock::default(),
+            _p: P::default(),
+        }
+    }
+}
+
+impl<T, P> PinCursor<T, P> {
+    // Moves everything over to a cursor with another pinning marker. The pattern names every
+    // field, so that a new one cannot be forgotten here.
+    fn remark<Q>(self, marker: Q) -> PinCursor<T, Q> {
+        let PinCursor {
+            c, read_faults, write_faults, affinity, read_fill, loopback, null_sink, sticky_eof,
+            eof_seen, char_aligned, exact_growth, close, error_on_full, strict_capacity, pending,
+            zero_write_limit, zero_writes, log, clamping_seeks, policy, gap_policy, marks, clock,
+            _p: _,
+        } = self;
+        PinCursor {
+            c, read_faults, write_faults, affinity, read_fill, loopback, null_sink, sticky_eof,
+            eof_seen, char_aligned, exact_growth, close, error_on_full, strict_capacity, pending,
+            zero_write_limit, zero_writes, log, clamping_seeks, policy, gap_policy, marks, clock,
+            _p: marker,
+        }
     }
 
     pub fn unwrap(self) -> Cursor<T> {
         self.c
     }
 
+    /// Takes a cursor that has been pinned in a box apart into its buffer, its position
+    /// and the counters it collected.
+    pub fn into_parts(self: Pin<Box<Self>>) -> (T, u64, Stats) {
+        let stats = self.stats();
+        let (data, pos) = self.into_inner_and_position();
+        (data, pos, stats)
+    }
+
+    /// Converts into a blocking `std::io::Cursor`, keeping the position.
+    pub fn into_std_cursor(self) -> std::io::Cursor<T> {
+        let pos = self.c.position();
+        let mut c = std::io::Cursor::new(self.c.into_inner());
+        c.set_position(pos);
+        c
+    }
+
+    pub fn get_ref(&self) -> &T {
+        self.c.get_ref()
+    }
+
+    pub(crate) fn buffer_mut(self: Pin<&mut Self>) -> &mut T {
+        self.project().c.get_mut()
+    }
+
     pub fn position(&self) -> u64 {
         self.c.position()
     }
 
-    pub fn set_position(self: Pin<&mut Self>, pos: u64) {
+    // Moves the position regardless of the position policy, for wrappers that manage it themselves.
+    pub(crate) fn set_position_unchecked(self: Pin<&mut Self>, pos: u64) {
         self.project().c.set_position(pos)
     }
 
-    pub fn write<'a>(self: Pin<&'a mut Self>, buf: &'a [u8]) -> impl Future<Output=Result<usize>> + 'a {
-        self.project().c.write(buf)
+    // Called when a read or write future is dropped, so that the next operation starts afresh.
+    pub(crate) fn abandon_read(self: Pin<&mut Self>) {
+        self.project().read_faults.abandon();
+    }
+
+    pub(crate) fn abandon_write(self: Pin<&mut Self>) {
+        self.project().write_faults.abandon();
+    }
+
+    /// The position advanced by `n`, or `None` if that does not fit into a `u64`.
+    pub fn position_checked_add(&self, n: u64) -> Option<u64> {
+        self.position().checked_add(n)
+    }
+
+    /// Same as [`unwrap`](PinCursor::unwrap), for a cursor that has already been pinned in a box.
+    pub fn unwrap_boxed(self: Pin<Box<Self>>) -> Cursor<T> {
+        // SAFETY: the cursor is `!Unpin` only to exercise the pinning code of its users;
+        // nothing in it depends on its address, so it may be moved out of its box.
+        unsafe { Pin::into_inner_unchecked(self) }.c
+    }
+
+    /// The backing buffer of a cursor that has been pinned in a box.
+    pub fn into_data(self: Pin<Box<Self>>) -> T {
+        self.unwrap_boxed().into_inner()
+    }
+
+    /// The backing buffer and the final position of a cursor that has been pinned in a box.
+    pub fn into_inner_and_position(self: Pin<Box<Self>>) -> (T, u64) {
+        let c = self.unwrap_boxed();
+        let pos = c.position();
+        (c.into_inner(), pos)
+    }
+}
+
+impl<T> PinCursor<T> {
+    /// Lets reads continue after a sticky EOF (see [`with_sticky_eof`](PinCursor::with_sticky_eof)).
+    pub fn reset_eof(self: Pin<&mut Self>) {
+        *self.project().eof_seen = false;
+    }
+
+    /// Swaps in a new backing buffer, returning the old one, and rewinds to position 0.
+    ///
+    /// This lets one pinned cursor be reused for many inputs.
+    pub fn replace_buffer(self: Pin<&mut Self>, new: T) -> T {
+        let c = self.project().c;
+        c.set_position(0);
+        std::mem::replace(c.get_mut(), new)
+    }
+
+    /// Takes the backing buffer out of a pinned cursor, leaving an empty one in its place.
+    ///
+    /// This works wherever the cursor is pinned, including on the stack with `stackpin`,
+    /// where it cannot be moved out of its slot.
+    pub fn take_buffer(self: Pin<&mut Self>) -> T
+        where T: Default
+    {
+        self.replace_buffer(T::default())
+    }
+}
+
+impl<T> UnpinCursor<T> {
+    /// Same as [`PinCursor::reset_eof`].
+    pub fn reset_eof(&mut self) {
+        self.eof_seen = false;
     }
 
-    pub fn read<'a>(self: Pin<&'a mut Self>, buf: &'a mut [u8]) -> impl Future<Output=Result<usize>> + 'a {
-        self.project().c.read(buf)
+    /// Same as [`PinCursor::replace_buffer`].
+    pub fn replace_buffer(&mut self, new: T) -> T {
+        self.c.set_position(0);
+        std::mem::replace(self.c.get_mut(), new)
     }
 
-    pub fn seek(self: Pin<&mut Self>, pos: SeekFrom) -> impl Future<Output=Result<u64>> + '_ {
-        self.project().c.seek(pos)
+    /// Same as [`PinCursor::take_buffer`].
+    pub fn take_buffer(&mut self) -> T
+        where T: Default
+    {
+        self.replace_buffer(T::default())
     }
 }
 
-impl<T> Read for PinCursor<T>
-    where T: Unpin,
-          Cursor<T>: Read
+impl<T, P> PinCursor<T, P>
+    where T: AsRef<[u8]>
 {
-    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
-        Pin::new(self.project().c).poll_read(cx, buf)
+    /// The whole backing buffer, regardless of the position.
+    pub fn bytes_ref(&self) -> &[u8] {
+        self.c.get_ref().as_ref()
     }
 
-    fn poll_read_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
-        Pin::new(self.project().c).poll_read_vectored(cx, bufs)
+    /// The length of the backing buffer, regardless of the position.
+    pub fn len(&self) -> usize {
+        self.bytes_ref().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies the bytes of the buffer into a new, independent cursor over a `Vec<u8>`,
+    /// at the same position.
+    ///
+    /// Only the data and the position are copied: the fork starts with no fault injection
+    /// and fresh counters.
+    pub fn fork(&self) -> PinCursor<Vec<u8>, P>
+        where P: Default
+    {
+        let mut c = Cursor::new(self.bytes_ref().to_vec());
+        c.set_position(self.position());
+        PinCursor::with_marker(c)
+    }
+
+    /// The part of the buffer before the position.
+    ///
+    /// If the position is past the end, this is the whole buffer.
+    pub fn filled(&self) -> &[u8] {
+        &self.bytes_ref()[..self.clamped_position()]
+    }
+
+    /// The part of the buffer from the position onwards, which reads would return.
+    ///
+    /// If the position is past the end, this is empty.
+    pub fn unread(&self) -> &[u8] {
+        &self.bytes_ref()[self.clamped_position()..]
+    }
+
+    /// The number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.unread().len()
+    }
+
+    /// Whether the position is at or past the end of the buffer, so that reads return `Ok(0)`.
+    pub fn at_end(&self) -> bool {
+        self.position() >= self.len() as u64
+    }
+
+    fn clamped_position(&self) -> usize {
+        self.position().min(self.len() as u64) as usize
+    }
+
+    // Where `set_position` goes for `pos`, as the position policy decides.
+    fn policy_position(&self, pos: u64) -> Result<u64> {
+        self.policy.apply(pos, self.len() as u64)
+    }
+
+    fn checked_position(&self, pos: u64) -> Result<u64> {
+        let len = self.len() as u64;
+        if pos > len {
+            return Err(position::out_of_range(pos, len));
+        }
+        Ok(pos)
+    }
+
+    fn marked_position(&self, name: &'static str) -> Result<u64> {
+        let pos = *self.marks.get(name).ok_or_else(|| Error::new(
+            ErrorKind::InvalidInput,
+            format!("no position is marked as {:?}", name),
+        ))?;
+        self.policy_position(pos)
     }
 }
 
-impl<T> Write for PinCursor<T>
-    where T: Unpin,
-          Cursor<T>: Write
+impl<T> PinCursor<T>
+    where T: AsRef<[u8]>
 {
-    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
-        Pin::new(self.project().c).poll_write(cx, buf)
+    /// Moves the position, following the [position policy](PinCursor::position_policy).
+    ///
+    /// # Panics
+    ///
+    /// With [`PositionPolicy::Strict`], panics if `pos` is past the end of the buffer.
+    pub fn set_position(self: Pin<&mut Self>, pos: u64) {
+        let pos = self.policy_position(pos).unwrap_or_else(|e| panic!("PinCursor::set_position: {}", e));
+        self.set_position_unchecked(pos)
     }
 
-    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<Result<usize>> {
-        Pin::new(self.project().c).poll_write_vectored(cx, bufs)
+    /// Moves the position, failing with `ErrorKind::InvalidInput` if it is past the end of
+    /// the buffer, whatever the position policy.
+    pub fn checked_set_position(self: Pin<&mut Self>, pos: u64) -> Result<()> {
+        let pos = self.checked_position(pos)?;
+        self.set_position_unchecked(pos);
+        Ok(())
+    }
+
+    /// Remembers the current position under `name`, replacing any earlier mark of that name.
+    pub fn mark(self: Pin<&mut Self>, name: &'static str) {
+        let pos = self.position();
+        self.project().marks.insert(name, pos);
+    }
+
+    /// Moves back (or forward) to the position marked as `name`, and returns it.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if there is no such mark, or if the
+    /// [position policy](PinCursor::position_policy) rejects the position because
+    /// the buffer has shrunk since it was marked.
+    pub fn seek_to_mark(self: Pin<&mut Self>, name: &'static str) -> Result<u64> {
+        let pos = self.marked_position(name)?;
+        self.set_position_unchecked(pos);
+        Ok(pos)
+    }
+
+    /// Reads synchronously, for tests that do not run an executor.
+    ///
+    /// Polls with a no-op waker until the read completes, and fails with `ErrorKind::TimedOut`
+    /// if it is still pending after [`SPIN_LIMIT`] polls.
+    pub fn spin_read(self: Pin<&mut Self>, buf: &mut [u8]) -> Result<usize> {
+        spin_read(self, buf)
+    }
+}
+
+impl<T> UnpinCursor<T>
+    where T: AsRef<[u8]>
+{
+    /// Same as [`PinCursor::set_position`].
+    pub fn set_position(&mut self, pos: u64) {
+        let pos = self.policy_position(pos).unwrap_or_else(|e| panic!("PinCursor::set_position: {}", e));
+        self.c.set_position(pos)
+    }
+
+    /// Same as [`PinCursor::checked_set_position`].
+    pub fn checked_set_position(&mut self, pos: u64) -> Result<()> {
+        let pos = self.checked_position(pos)?;
+        self.c.set_position(pos);
+        Ok(())
+    }
+
+    /// Same as [`PinCursor::mark`].
+    pub fn mark(&mut self, name: &'static str) {
+        let pos = self.position();
+        self.marks.insert(name, pos);
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
-        Pin::new(self.project().c).poll_flush(cx)
+    /// Same as [`PinCursor::seek_to_mark`].
+    pub fn seek_to_mark(&mut self, name: &'static str) -> Result<u64> {
+        let pos = self.marked_position(name)?;
+        self.c.set_position(pos);
+        Ok(pos)
     }
 
-    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
-        Pin::new(self.project().c).poll_close(cx)
+    /// Same as [`PinCursor::spin_read`].
+    pub fn spin_read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        spin_read(Pin::new(self), buf)
     }
 }
 
-impl<T> Seek for PinCursor<T>
-    where T: Unpin,
-          Cursor<T>: Seek
+// Polls a read with a no-op waker until it completes, up to `SPIN_LIMIT` times.
+fn spin_read<R>(mut r: Pin<&mut R>, buf: &mut [u8]) -> Result<usize>
+    where R: Read + ?Sized
 {
-    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
-        Pin::new(self.project().c).poll_seek(cx, pos)
+    let mut cx = Context::from_waker(Waker::noop());
+    for _ in 0..SPIN_LIMIT {
+        if let Poll::Ready(result) = r.as_mut().poll_read(&mut cx, buf) {
+            return result;
+        }
     }
+    Err(Error::new(ErrorKind::TimedOut, "read still pending after SPIN_LIMIT polls"))
 }
 
-#[cfg(test)]
-mod tests {
-    use static_assertions::{assert_impl_all, assert_not_impl_all};
+/// Operations as futures. An [`UnpinCursor`] gets these from the async-std extension traits.
+impl<T> PinCursor<T>
+    where T: AsRef<[u8]>
+{
+    /// Reads into `buf`. The buffer only has to live as long as the future, not the cursor borrow.
+    pub fn read<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> ReadFuture<'a, 'b, T> {
+        ReadFuture { cursor: self, buf }
+    }
 
-    use super::*;
+    pub fn seek(self: Pin<&mut Self>, pos: SeekFrom) -> SeekFuture<'_, T> {
+        SeekFuture { cursor: self, pos }
+    }
 
-    #[test]
-    fn impls() {
-        assert_not_impl_all!(PinCursor<Vec<u8>>: Unpin);
-        assert_impl_all!(PinCursor<Vec<u8>>: Read, Write, Seek);
+    /// Same as [`read`](PinCursor::read), but the future is `!Unpin`, like the cursor itself.
+    pub fn read_pinned<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> Pinned<ReadFuture<'a, 'b, T>> {
+        Pinned::new(self.read(buf))
+    }
+
+    /// Same as [`seek`](PinCursor::seek), but the future is `!Unpin`.
+    pub fn seek_pinned(self: Pin<&mut Self>, pos: SeekFrom) -> Pinned<SeekFuture<'_, T>> {
+        Pinned::new(self.seek(pos))
+    }
+
+    /// Same as [`read`](PinCursor::read), but boxed. The future lives as long as both borrows.
+    pub fn read_boxed<'a, 'b, 'f>(self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> Pin<Box<dyn Future<Output=Result<usize>> + 'f>>
+        where 'a: 'f, 'b: 'f, T: 'f
+    {
+        Box::pin(self.read(buf))
+    }
+
+    /// Same as [`seek`](PinCursor::seek), but boxed.
+    pub fn seek_boxed<'a>(self: Pin<&'a mut Self>, pos: SeekFrom) -> Pin<Box<dyn Future<Output=Result<u64>> + 'a>>
+        where T: 'a
+    {
+        Box::pin(self.seek(pos))
+    }
+
+    /// Reads up to and including `delim`, or up to EOF, and appends the bytes to `buf`.
+    ///
+    /// Returns the number of bytes read, which is 0 at EOF.
+    pub fn read_until<'a, 'b>(self: Pin<&'a mut Self>, delim: u8, buf: &'b mut Vec<u8>) -> ReadUntilFuture<'a, 'b, T> {
+        ReadUntilFuture { cursor: self, delim, buf, read: 0 }
+    }
+
+    /// Reads a line, including the trailing `\n` if there is one, and appends it to `buf`.
+    ///
+    /// Returns the number of bytes read, which is 0 at EOF. If the line is not valid UTF-8,
+    /// fails with `ErrorKind::InvalidData` and leaves `buf` untouched.
+    ///
+    /// This is not cancel-safe: the bytes read by a future that is dropped before it completes
+    /// are lost. See [Cancellation](crate#cancellation).
+    pub fn read_line<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut String) -> ReadLineFuture<'a, 'b, T> {
+        ReadLineFuture { cursor: self, buf, bytes: Vec::new(), read: 0 }
+    }
+
+    /// Reads everything from the position to the end into a new vector.
+    ///
+    /// Fault injection applies as usual; if it fails a read, the error is returned and the bytes
+    /// read before it are lost, although the position has moved past them.
+    /// The same goes for dropping the future before it completes: this is not cancel-safe.
+    pub fn collect_all(self: Pin<&mut Self>) -> CollectAllFuture<'_, T> {
+        CollectAllFuture { cursor: self, buf: Vec::new(), filled: 0 }
+    }
+
+    /// Reads into a buffer that does not have to be initialized first, and resolves to
+    /// the initialized prefix of `buf`, which holds the bytes read.
+    ///
+    /// Behaves like [`read`](PinCursor::read), except that the rest of `buf` is left untouched
+    /// even with [`with_read_fill`](PinCursor::with_read_fill). The result borrows from `buf`
+    /// only, so the cursor can be used again while it is alive.
+    pub fn read_uninit<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut [MaybeUninit<u8>]) -> ReadUninitFuture<'a, 'b, T> {
+        ReadUninitFuture { cursor: self, buf: Some(buf) }
+    }
+
+}
+
+impl<T> PinCursor<T>
+    where T: Buffer
+{
+    /// Writes from `buf`. Like with [`read`](PinCursor::read), the lifetimes of the buffer and
+    /// of the cursor borrow are independent.
+    pub fn write<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b [u8]) -> WriteFuture<'a, 'b, T> {
+        WriteFuture { cursor: self, buf }
+    }
+
+    /// Same as [`write`](PinCursor::write), but the future is `!Unpin`.
+    pub fn write_pinned<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b [u8]) -> Pinned<WriteFuture<'a, 'b, T>> {
+        Pinned::new(self.write(buf))
+    }
+
+    /// Same as [`write`](PinCursor::write) with the UTF-8 bytes of `s`.
+    pub fn write_str<'a, 'b>(self: Pin<&'a mut Self>, s: &'b str) -> WriteFuture<'a, 'b, T> {
+        self.write(s.as_bytes())
+    }
+
+    /// Same as [`write`](PinCursor::write), but boxed, so that futures of different cursors
+    /// can be stored together as trait objects.
+    pub fn write_boxed<'a, 'b, 'f>(self: Pin<&'a mut Self>, buf: &'b [u8]) -> Pin<Box<dyn Future<Output=Result<usize>> + 'f>>
+        where 'a: 'f, 'b: 'f, T: 'f
+    {
+        Box::pin(self.write(buf))
+    }
+}
+
+impl<T> PinCursor<T>
+    where T: AsMut<[u8]>
+{
+    /// Direct mutable access to the whole backing buffer, for preparing input in place.
+    ///
+    /// This bypasses the position, the counters and fault injection entirely.
+    pub fn bytes_mut(self: Pin<&mut Self>) -> &mut [u8] {
+        self.project().c.get_mut().as_mut()
+    }
+}
+
+impl<T> UnpinCursor<T>
+    where T: AsMut<[u8]>
+{
+    /// Same as [`PinCursor::bytes_mut`].
+    pub fn bytes_mut(&mut self) -> &mut [u8] {
+        self.c.get_mut().as_mut()
+    }
+}
+
+impl<T> PinCursor<T>
+    where T: Unpin
+{
+    /// The wrapped `Cursor`, for poll functions that call its own poll methods with logic of
+    /// their own around them, or for implementing traits that this crate does not know about
+    /// on wrapper types.
+    ///
+    /// Like [`bytes_mut`](PinCursor::bytes_mut), this goes around the position policy,
+    /// the counters, the op log and fault injection; polls made through it are not seen by
+    /// the `PinCursor` at all. Prefer it over taking the cursor apart or re-wrapping it when
+    /// you need the position and the buffer to stay in the pinned cursor. The `Cursor` is
+    /// `Unpin`, so it can be unpinned with `Pin::get_mut` when plain `&mut` access is enough;
+    /// it is returned pinned so that it can go straight into poll methods.
+    pub fn project_cursor(self: Pin<&mut Self>) -> Pin<&mut Cursor<T>> {
+        Pin::new(self.project().c)
+    }
+}
+
+impl<T> UnpinCursor<T>
+    where T: Unpin
+{
+    /// Same as [`PinCursor::project_cursor`].
+    pub fn project_cursor(&mut self) -> Pin<&mut Cursor<T>> {
+        Pin::new(&mut self.c)
+    }
+}
+
+/// Fault injection.
+///
+/// These are meant to be chained onto [`wrap`](PinCursor::wrap) before the cursor is pinned.
+/// When several modes are active in one direction, each poll applies them in this order:
+/// the schedule caps the chunk size, then the byte limit may fail the poll (before anything
+/// is transferred), then pending stalls are returned before the chunk goes through.
+/// Vectored calls are served from the first non-empty buffer.
+impl<T, P> PinCursor<T, P> {
+    /// Caps the size of successive reads: the n-th read transfers at most `sizes[n]` bytes.
+    /// Once the schedule is exhausted, reads are no longer capped.
+    pub fn with_read_schedule(mut self, sizes: impl IntoIterator<Item=usize>) -> Self {
+        self.read_faults.set_schedule(sizes);
+        self
+    }
+
+    /// Caps the size of successive writes, like [`with_read_schedule`](PinCursor::with_read_schedule).
+    pub fn with_write_schedule(mut self, sizes: impl IntoIterator<Item=usize>) -> Self {
+        self.write_faults.set_schedule(sizes);
+        self
+    }
+
+    /// Configures the schedules and failures to replay a captured IO trace.
+    ///
+    /// The trace has one operation per line: `read <size>` and `write <size>` add to the
+    /// read and write schedules, and `read error` or `write error` fails that direction
+    /// once the sizes before it have gone through. Blank lines and `#` comments are ignored.
+    /// Malformed traces are rejected with `ErrorKind::InvalidData`, naming the line.
+    ///
+    /// ```
+    /// # use pin_cursor::PinCursor;
+    /// # use async_std::io::Cursor;
+    /// let cursor = PinCursor::wrap(Cursor::new(vec![0u8; 16]))
+    ///     .with_replay("read 3\nwrite 1\nread 2\nread error\n")
+    ///     .unwrap();
+    /// ```
+    pub fn with_replay(self, trace: &str) -> Result<Self> {
+        let (reads, writes) = replay::parse(trace)?;
+        let mut cursor = self.with_read_schedule(reads.sizes).with_write_schedule(writes.sizes);
+        if let Some(bytes) = reads.fail_after {
+            cursor = cursor.fail_read_after(bytes);
+        }
+        if let Some(bytes) = writes.fail_after {
+            cursor = cursor.fail_write_after(bytes);
+        }
+        Ok(cursor)
+    }
+
+    /// Fails, with `ErrorKind::Other`, any read that would carry the total number of bytes read past `bytes`.
+    pub fn fail_read_after(mut self, bytes: u64) -> Self {
+        self.read_faults.set_fail_after(bytes);
+        self
+    }
+
+    /// Fails, with `ErrorKind::Other`, any write that would carry the total number of bytes written past `bytes`.
+    pub fn fail_write_after(mut self, bytes: u64) -> Self {
+        self.write_faults.set_fail_after(bytes);
+        self
+    }
+
+    /// Makes every `n`-th read fail with `ErrorKind::Interrupted` without consuming any data,
+    /// so that the next read picks up where the interrupted one would have.
+    ///
+    /// Well-behaved code retries on `Interrupted` (as `read_exact` and `read_to_end` do)
+    /// rather than giving up. Panics if `n` is 0.
+    pub fn with_interrupt_every(mut self, n: u64) -> Self {
+        self.read_faults.set_interrupt_every(n);
+        self
+    }
+
+    /// Makes every read return `Poll::Pending` (waking immediately) `polls` times before completing.
+    pub fn stall_reads(mut self, polls: u32) -> Self {
+        self.read_faults.set_stalls(polls);
+        self
+    }
+
+    /// Makes every write return `Poll::Pending` (waking immediately) `polls` times before completing.
+    pub fn stall_writes(mut self, polls: u32) -> Self {
+        self.write_faults.set_stalls(polls);
+        self
+    }
+
+    /// Makes reads and writes misbehave at random, as described by `profile`.
+    ///
+    /// The randomness comes from a simple generator seeded with `seed`, so the same seed
+    /// and the same sequence of polls always produce the same faults. Reads and writes
+    /// draw from separate sequences. Random faults apply on top of the other modes:
+    /// chunks get the smaller of the two limits, and random stalls come after scheduled ones.
+    pub fn with_random_faults(mut self, seed: u64, profile: FaultProfile) -> Self {
+        self.read_faults.set_random(seed, profile);
+        self.write_faults.set_random(!seed, profile);
+        self
+    }
+
+    /// Makes every poll panic unless it happens on the same thread as the first poll.
+    ///
+    /// This is opt-in because multi-threaded executors legitimately move tasks between threads.
+    pub fn assert_same_thread(mut self) -> Self {
+        self.affinity.enable();
+        self
+    }
+
+    /// Overwrites the part of each read buffer that a read did not fill with `byte`.
+    ///
+    /// This surfaces code that looks past the returned length into stale buffer contents.
+    pub fn with_read_fill(mut self, byte: u8) -> Self {
+        self.read_fill = Some(byte);
+        self
+    }
+
+    /// Turns the cursor into a single-buffer pipe: writes always append to the end of the buffer
+    /// and leave the position alone, so it trails behind them and reads return what was written.
+    ///
+    /// When reads catch up with writes, they return `Ok(0)`, as at the end of any cursor;
+    /// a later write makes more bytes available again.
+    pub fn with_loopback(mut self) -> Self {
+        self.loopback = true;
+        self
+    }
+
+    /// Decouples the write head from the read head: writes always append to the end of the
+    /// buffer, while reads use and advance the position, as in a log file opened for appending.
+    ///
+    /// This is the same mode as [`with_loopback`](PinCursor::with_loopback), named for cursors
+    /// that start out with data in them.
+    pub fn with_append_writes(self) -> Self {
+        self.with_loopback()
+    }
+
+    /// Makes EOF final: once a read hits the end of the buffer, all further reads return `Ok(0)`,
+    /// like on a consumed network stream.
+    ///
+    /// Unlike with a plain `Cursor`, seeking back or writing more data does not make anything
+    /// readable again; only [`reset_eof`](PinCursor::reset_eof) does.
+    pub fn with_sticky_eof(mut self) -> Self {
+        self.sticky_eof = true;
+        self
+    }
+
+    /// Makes reads end on UTF-8 character boundaries, for testing consumers of text that
+    /// assume that chunks never split a character.
+    ///
+    /// A read that would end in the middle of a character is shortened to the last boundary
+    /// before it. If the buffer (or the read schedule) does not leave room for even the first
+    /// character, the read is not shortened, and does split it.
+    /// To also reject invalid text on writes, back the cursor with a `String`.
+    pub fn char_aligned(mut self) -> Self {
+        self.char_aligned = true;
+        self
+    }
+
+    /// Makes writes that extend a growable buffer allocate exactly as much as they need,
+    /// instead of the usual amortized doubling, so that capacity is predictable in tests.
+    pub fn with_exact_growth(mut self) -> Self {
+        self.exact_growth = true;
+        self
+    }
+
+    /// Makes writes accept their bytes and throw them away, like `/dev/null`.
+    ///
+    /// Neither the buffer nor the position change, but the write counters, the schedule
+    /// and failure injection all work as usual, so memory stays flat in long-running tests.
+    pub fn with_null_sink(mut self) -> Self {
+        self.null_sink = true;
+        self
+    }
+
+    /// Makes writes and flushes fail with `kind` once `poll_close` has been called.
+    ///
+    /// By default, like `std::io::Cursor`, the cursor keeps accepting writes after being closed.
+    pub fn with_error_after_close(mut self, kind: ErrorKind) -> Self {
+        self.close.set_error_after_close(kind);
+        self
+    }
+
+    /// Makes writes that a full fixed-size buffer cannot take any byte of fail with
+    /// `ErrorKind::WriteZero`, instead of returning `Ok(0)` that naive write loops spin on.
+    pub fn error_on_full(mut self) -> Self {
+        self.error_on_full = true;
+        self
+    }
+
+    /// Rejects, with `ErrorKind::WriteZero`, any write that would make the buffer longer than
+    /// `cap` bytes, like a sink that refuses oversized frames.
+    ///
+    /// The rejection is atomic: nothing of the write is taken, not even the part that would fit.
+    pub fn with_strict_capacity(mut self, cap: u64) -> Self {
+        self.strict_capacity = Some(cap);
+        self
+    }
+
+    /// Makes writes wait for a flush, like a buffered writer: `poll_write` accepts the bytes and
+    /// advances the position, but keeps them aside, out of sight of [`get_ref`](PinCursor::get_ref),
+    /// [`filled`](PinCursor::filled) and reads, until `poll_flush` or `poll_close` copies them
+    /// into the buffer, in order.
+    ///
+    /// For fixed-size buffers, a staged write that does not fit fails the flush with
+    /// `ErrorKind::WriteZero`, and the rest of the staged writes is discarded.
+    /// Bytes that have not been flushed are lost when the cursor is taken apart.
+    pub fn with_buffered_writes(mut self) -> Self {
+        self.pending = Some(Vec::new());
+        self
+    }
+
+    /// Panics once more than `n` writes in a row have returned `Ok(0)` for a non-empty buffer,
+    /// turning a write loop that would hang into a test failure.
+    pub fn max_zero_writes(mut self, n: u32) -> Self {
+        self.zero_write_limit = Some(n);
+        self
+    }
+
+    /// Gives closing a meaning: once `poll_close` has succeeded, writes and flushes fail with
+    /// `ErrorKind::BrokenPipe` (or the kind given to
+    /// [`with_error_after_close`](PinCursor::with_error_after_close)), and reads behave as `reads` says.
+    /// Closing again is a no-op that succeeds.
+    pub fn with_strict_close(mut self, reads: ReadsAfterClose) -> Self {
+        self.close.set_strict(reads);
+        self
+    }
+
+    /// Makes dropping the cursor panic if it has never been closed, to catch code that
+    /// forgets to shut its IO down.
+    pub fn expect_closed(mut self) -> Self {
+        self.close.expect_closed();
+        self
+    }
+
+    /// Whether `poll_close` has been called.
+    pub fn is_closed(&self) -> bool {
+        self.close.is_closed()
+    }
+
+    /// Makes seeks clamp their target to the range from 0 to the end of the buffer instead of
+    /// failing on targets before the start (or beyond `u64::MAX`).
+    ///
+    /// By default, such seeks fail with `ErrorKind::InvalidInput`, naming the attempted offset
+    /// in the message, and leave the position unchanged. Seeks past the end always succeed
+    /// in the default mode, as with `std::io::Cursor`.
+    pub fn clamping_seeks(mut self) -> Self {
+        self.clamping_seeks = true;
+        self
+    }
+
+    /// Chooses what [`set_position`](PinCursor::set_position) and seeks do with positions past
+    /// the end of the buffer. The default, [`PositionPolicy::Lenient`], keeps them.
+    ///
+    /// [`clamping_seeks`](PinCursor::clamping_seeks) takes precedence for seeks.
+    pub fn position_policy(mut self, policy: PositionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Chooses what writes starting past the end of the buffer do with the gap.
+    /// The default, [`GapPolicy::ZeroFill`], fills it with zeroes.
+    pub fn gap_policy(mut self, policy: GapPolicy) -> Self {
+        self.gap_policy = policy;
+        self
+    }
+
+    /// Makes every successfully completed operation advance a virtual clock by the duration
+    /// `cost` returns; see [`elapsed`](PinCursor::elapsed). Nothing actually sleeps.
+    pub fn with_clock(mut self, cost: impl Fn() -> Duration + Send + Sync + 'static) -> Self {
+        self.clock.set_cost(cost);
+        self
+    }
+
+    /// The virtual time spent in IO since [`with_clock`](PinCursor::with_clock) was called.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Starts recording every successfully completed operation; see [`op_log`](PinCursor::op_log).
+    pub fn with_op_log(mut self) -> Self {
+        self.log.enable();
+        self
+    }
+
+    /// The operations recorded since [`with_op_log`](PinCursor::with_op_log) was called, oldest first.
+    pub fn op_log(&self) -> &[Op] {
+        self.log.ops()
+    }
+
+    /// Iterates over the recorded operations, oldest first.
+    pub fn ops(&self) -> impl Iterator<Item=&Op> {
+        self.log.ops().iter()
+    }
+
+    /// The recorded [`Op::Read`]s, oldest first.
+    pub fn reads(&self) -> impl Iterator<Item=&Op> {
+        self.ops().filter(|op| matches!(op, Op::Read { .. }))
+    }
+
+    /// The recorded [`Op::Write`]s, oldest first.
+    pub fn writes(&self) -> impl Iterator<Item=&Op> {
+        self.ops().filter(|op| matches!(op, Op::Write { .. }))
+    }
+
+    /// The recorded [`Op::Seek`]s, oldest first.
+    pub fn seeks(&self) -> impl Iterator<Item=&Op> {
+        self.ops().filter(|op| matches!(op, Op::Seek { .. }))
+    }
+
+    /// The bytes read and written, in that order, by the recorded operations with indices in
+    /// `range`. The part of the range past the end of the log is ignored.
+    pub fn bytes_in_range(&self, range: Range<usize>) -> (u64, u64) {
+        let ops = self.op_log();
+        let end = range.end.min(ops.len());
+        let start = range.start.min(end);
+        ops[start..end].iter().fold((0, 0), |(read, written), op| match op {
+            Op::Read { len, .. } => (read + *len as u64, written),
+            Op::Write { len, .. } => (read, written + *len as u64),
+            _ => (read, written),
+        })
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.read_faults.transferred()
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.write_faults.transferred()
+    }
+
+    /// The number of reads that returned at least one byte.
+    ///
+    /// Unlike [`bytes_read`](PinCursor::bytes_read), this shows how fragmented the consumer's IO is.
+    pub fn read_calls(&self) -> u64 {
+        self.read_faults.calls()
+    }
+
+    /// The number of writes that accepted at least one byte.
+    pub fn write_calls(&self) -> u64 {
+        self.write_faults.calls()
+    }
+
+    /// All the counters at once.
+    pub fn stats(&self) -> Stats {
+        Stats {
+            bytes_read: self.bytes_read(),
+            bytes_written: self.bytes_written(),
+            read_calls: self.read_calls(),
+            write_calls: self.write_calls(),
+        }
+    }
+}
+
+impl<T> PinCursor<T> {
+    /// Zeroes the byte and call counters, leaving the position and the buffer alone.
+    ///
+    /// This allows assertions about a single phase of a longer session. Limits set with
+    /// [`fail_read_after`](PinCursor::fail_read_after) and its sibling keep counting
+    /// from the start of the session.
+    pub fn reset_counters(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.read_faults.reset_counters();
+        this.write_faults.reset_counters();
+    }
+}
+
+impl<T> UnpinCursor<T> {
+    /// Same as [`PinCursor::reset_counters`].
+    pub fn reset_counters(&mut self) {
+        self.read_faults.reset_counters();
+        self.write_faults.reset_counters();
+    }
+}
+
+impl<const N: usize> PinCursor<[u8; N]> {
+    /// Wraps an owned fixed-size array, for tests that should not touch the heap.
+    ///
+    /// Writes cannot grow the array: a write running past its end is short,
+    /// and a write at the end returns `Ok(0)`.
+    pub fn from_array(data: [u8; N]) -> Self {
+        Self::wrap(Cursor::new(data))
+    }
+}
+
+impl PinCursor<Vec<u8>> {
+    /// Reserves room for at least `additional` more bytes past the end of the buffer,
+    /// so that writes within it do not reallocate.
+    pub fn reserve(self: Pin<&mut Self>, additional: usize) {
+        self.buffer_mut().reserve(additional)
+    }
+}
+
+impl UnpinCursor<Vec<u8>> {
+    /// Same as [`PinCursor::reserve`].
+    pub fn reserve(&mut self, additional: usize) {
+        self.c.get_mut().reserve(additional)
+    }
+}
+
+impl<'a> PinCursor<&'a [u8]> {
+    /// Wraps an immutable slice, such as a byte string literal, for tests that only read.
+    ///
+    /// ```
+    /// # use pin_cursor::PinCursor;
+    /// # use async_std::task::block_on;
+    /// let mut cursor = Box::pin(PinCursor::from_readonly(b"hello"));
+    /// let data = block_on(cursor.as_mut().collect_all()).unwrap();
+    /// assert_eq!(data, b"hello");
+    /// ```
+    pub fn from_readonly(data: &'a [u8]) -> Self {
+        Self::wrap(Cursor::new(data))
+    }
+}
+
+impl<P> PinCursor<Cow<'_, [u8]>, P> {
+    /// Whether the data has been copied into an owned vector, which the first write does.
+    pub fn is_owned(&self) -> bool {
+        matches!(self.get_ref(), Cow::Owned(_))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl PinCursor<bytes::BytesMut> {
+    /// Takes the buffer out as `Bytes`, leaving an empty one and rewinding to position 0.
+    pub fn freeze(self: Pin<&mut Self>) -> bytes::Bytes {
+        self.take_buffer().freeze()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl UnpinCursor<bytes::BytesMut> {
+    /// Same as [`PinCursor::freeze`].
+    pub fn freeze(&mut self) -> bytes::Bytes {
+        self.take_buffer().freeze()
+    }
+}
+
+/// Appends to the end of the buffer, not at the position, which stays where it was.
+impl<P> Extend<u8> for PinCursor<Vec<u8>, P> {
+    fn extend<I: IntoIterator<Item=u8>>(&mut self, iter: I) {
+        self.c.get_mut().extend(iter)
+    }
+}
+
+/// Appends to the end of the buffer, not at the position, which stays where it was.
+impl<'a, P> Extend<&'a u8> for PinCursor<Vec<u8>, P> {
+    fn extend<I: IntoIterator<Item=&'a u8>>(&mut self, iter: I) {
+        self.c.get_mut().extend(iter)
+    }
+}
+
+impl<T> From<std::io::Cursor<T>> for PinCursor<T> {
+    /// Wraps the data of a blocking `std::io::Cursor`, keeping the position.
+    fn from(c: std::io::Cursor<T>) -> Self {
+        let pos = c.position();
+        let mut c = Cursor::new(c.into_inner());
+        c.set_position(pos);
+        Self::wrap(c)
+    }
+}
+
+impl<T, P> fmt::Display for PinCursor<T, P>
+    where T: AsRef<[u8]>
+{
+    /// A one-line summary of the position and the counters, without the buffer contents.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = self.stats();
+        write!(
+            f,
+            "PinCursor(pos={}/{}, read={}B/{}calls, write={}B/{}calls)",
+            self.position(),
+            self.c.get_ref().as_ref().len(),
+            stats.bytes_read,
+            stats.read_calls,
+            stats.bytes_written,
+            stats.write_calls,
+        )
+    }
+}
+
+impl<'a> From<Cow<'a, [u8]>> for PinCursor<Cow<'a, [u8]>> {
+    /// Wraps fixture data that may be either borrowed or owned, positioned at the start.
+    fn from(data: Cow<'a, [u8]>) -> Self {
+        Self::wrap(Cursor::new(data))
+    }
+}
+
+// Shortens a read of `len` bytes from the start of `rest` so that it does not end inside
+// a UTF-8 sequence, unless that would leave nothing to read.
+fn char_aligned(rest: &[u8], len: usize) -> usize {
+    let is_continuation = |i: usize| rest.get(i).is_some_and(|&b| b & 0b1100_0000 == 0b1000_0000);
+    match (1..=len).rev().find(|&end| !is_continuation(end)) {
+        Some(end) => end,
+        None => len,
+    }
+}
+
+impl<T, P> PinCursor<T, P>
+    where T: AsRef<[u8]>
+{
+    // Everything a read of up to `len` bytes does except copying them out: returns the bytes read.
+    fn poll_read_slice(self: Pin<&mut Self>, cx: &mut Context<'_>, len: usize) -> Poll<Result<&[u8]>> {
+        let this = self.project();
+        if len == 0 || *this.eof_seen || this.close.reads_eof() {
+            return Poll::Ready(Ok(&[]));
+        }
+        let pos = this.c.position();
+        let data_len = this.c.get_ref().as_ref().len() as u64;
+        let available = data_len.saturating_sub(pos).min(len as u64) as usize;
+        let mut chunk = ready!(this.read_faults.poll_begin(cx, available))?;
+        if *this.char_aligned && chunk > 0 {
+            chunk = char_aligned(&this.c.get_ref().as_ref()[pos as usize..], chunk);
+        }
+        if available == 0 && *this.sticky_eof {
+            *this.eof_seen = true;
+        }
+        this.read_faults.finish(chunk);
+        this.log.record(Op::Read { pos, len: chunk });
+        this.clock.tick();
+        if chunk == 0 {
+            return Poll::Ready(Ok(&[]));
+        }
+        this.c.set_position(pos + chunk as u64);
+        let start = pos as usize;
+        Poll::Ready(Ok(&this.c.get_ref().as_ref()[start..start + chunk]))
+    }
+}
+
+impl<T, P> Read for PinCursor<T, P>
+    where T: AsRef<[u8]>
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        self.as_mut().project().affinity.check("poll_read");
+        let src = ready!(self.as_mut().poll_read_slice(cx, buf.len()))?;
+        let n = src.len();
+        buf[..n].copy_from_slice(src);
+        if let Some(byte) = self.read_fill {
+            buf[n..].fill(byte);
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_read_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
+        match bufs.iter_mut().find(|b| !b.is_empty()) {
+            Some(buf) => self.poll_read(cx, buf),
+            None => Poll::Ready(Ok(0)),
+        }
+    }
+}
+
+impl<T, P> BufRead for PinCursor<T, P>
+    where T: AsRef<[u8]>
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        let this = self.project();
+        this.affinity.check("poll_fill_buf");
+        if *this.eof_seen || this.close.reads_eof() {
+            return Poll::Ready(Ok(&[]));
+        }
+        let data = this.c.get_ref().as_ref();
+        let start = (this.c.position().min(data.len() as u64)) as usize;
+        let mut chunk = ready!(this.read_faults.poll_begin(cx, data.len() - start))?;
+        if *this.char_aligned {
+            chunk = char_aligned(&data[start..], chunk);
+        }
+        if start == data.len() && *this.sticky_eof {
+            *this.eof_seen = true;
+        }
+        Poll::Ready(Ok(&data[start..start + chunk]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        this.affinity.check("consume");
+        let pos = this.c.position();
+        this.c.set_position(pos.saturating_add(amt as u64));
+        this.read_faults.finish(amt);
+        if amt > 0 {
+            this.log.record(Op::Read { pos, len: amt });
+            this.clock.tick();
+        }
+    }
+}
+
+impl<T, P> Write for PinCursor<T, P>
+    where T: Buffer
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        this.affinity.check("poll_write");
+        this.close.check_write()?;
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        // The length the buffer will have once the writes staged by `with_buffered_writes` land.
+        let staged_end = this.pending.iter().flatten().map(|(pos, data)| pos + data.len() as u64).max();
+        let len = staged_end.unwrap_or(0).max(this.c.get_ref().as_ref().len() as u64);
+        if !*this.loopback && !*this.null_sink {
+            this.gap_policy.check(this.c.position(), len)?;
+        }
+        if let (Some(cap), false) = (*this.strict_capacity, *this.null_sink) {
+            let pos = if *this.loopback { len } else { this.c.position() };
+            if pos.saturating_add(buf.len() as u64).max(len) > cap {
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::WriteZero,
+                    format!("write of {} bytes at {} would exceed the capacity of {} bytes", buf.len(), pos, cap),
+                )));
+            }
+        }
+        let chunk = ready!(this.write_faults.poll_begin(cx, buf.len()))?;
+        let pos = if *this.loopback { len } else { this.c.position() };
+        if pos.checked_add(chunk as u64).is_none() {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("write of {} bytes at {} overflows the position", chunk, pos),
+            )));
+        }
+        if *this.null_sink {
+            this.write_faults.finish(chunk);
+            this.log.record(Op::Write { pos, len: chunk });
+            this.clock.tick();
+            return Poll::Ready(Ok(chunk));
+        }
+        if let Some(pending) = this.pending {
+            pending.push((pos, buf[..chunk].to_vec()));
+            if !*this.loopback {
+                this.c.set_position(pos + chunk as u64);
+            }
+            this.write_faults.finish(chunk);
+            this.log.record(Op::Write { pos, len: chunk });
+            this.clock.tick();
+            return Poll::Ready(Ok(chunk));
+        }
+        if *this.exact_growth {
+            let len = this.c.get_ref().as_ref().len() as u64;
+            let end = pos.saturating_add(chunk as u64);
+            // Impossible sizes are left for `write_at` to reject.
+            if end > len && end <= isize::MAX as u64 {
+                this.c.get_mut().reserve_exact((end - len) as usize);
+            }
+        }
+        let n = this.c.get_mut().write_at(pos, &buf[..chunk])?;
+        if n == 0 {
+            let capacity = this.c.get_ref().as_ref().len();
+            if *this.error_on_full {
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::WriteZero,
+                    format!("write of {} bytes at {} does not fit into a buffer of {} bytes", chunk, pos, capacity),
+                )));
+            }
+            *this.zero_writes += 1;
+            if let Some(limit) = *this.zero_write_limit {
+                assert!(*this.zero_writes <= limit, "PinCursor: {} writes in a row returned Ok(0); is a write loop stuck?", this.zero_writes);
+            }
+        } else {
+            *this.zero_writes = 0;
+        }
+        if !*this.loopback {
+            this.c.set_position(pos.saturating_add(n as u64));
+        }
+        this.write_faults.finish(n);
+        this.log.record(Op::Write { pos, len: n });
+        this.clock.tick();
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<Result<usize>> {
+        match bufs.iter().find(|b| !b.is_empty()) {
+            Some(buf) => self.poll_write(cx, buf),
+            None => Poll::Ready(Ok(0)),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.project();
+        this.affinity.check("poll_flush");
+        this.close.check_flush()?;
+        commit_pending(this.c.get_mut(), this.pending)?;
+        this.log.record(Op::Flush);
+        this.clock.tick();
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.project();
+        this.affinity.check("poll_close");
+        if this.close.is_closed() {
+            return Poll::Ready(Ok(()));
+        }
+        commit_pending(this.c.get_mut(), this.pending)?;
+        this.close.close();
+        this.log.record(Op::Close);
+        this.clock.tick();
+        Poll::Ready(Ok(()))
+    }
+}
+
+// Copies the writes staged by `with_buffered_writes` into the buffer.
+fn commit_pending<T: Buffer>(buffer: &mut T, pending: &mut Option<Vec<(u64, Vec<u8>)>>) -> Result<()> {
+    for (pos, data) in pending.iter_mut().flat_map(|p| p.drain(..)) {
+        if buffer.write_at(pos, &data)? < data.len() {
+            return Err(Error::new(
+                ErrorKind::WriteZero,
+                format!("staged write of {} bytes at {} does not fit into the buffer", data.len(), pos),
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl<T, P> Seek for PinCursor<T, P>
+    where T: AsRef<[u8]>
+{
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.project();
+        this.affinity.check("poll_seek");
+        let len = this.c.get_ref().as_ref().len() as u64;
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => (n, 0),
+            SeekFrom::End(n) => (len, n),
+            SeekFrom::Current(n) => (this.c.position(), n),
+        };
+        let resolved = match base.checked_add_signed(offset) {
+            Some(n) if !*this.clamping_seeks => this.policy.apply(n, len)?,
+            Some(n) => n.min(len),
+            None if *this.clamping_seeks => if offset < 0 { 0 } else { len },
+            None => {
+                let target = i128::from(base) + i128::from(offset);
+                let problem = if target < 0 { "is before the start" } else { "overflows the position" };
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid seek to absolute offset {}, which {}", target, problem),
+                )));
+            }
+        };
+        this.c.set_position(resolved);
+        this.log.record(Op::Seek { target: pos, pos: resolved });
+        this.clock.tick();
+        Poll::Ready(Ok(resolved))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::io::ErrorKind;
+    use std::task::Waker;
+
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_all!(PinCursor<Vec<u8>>: Unpin);
+        assert_impl_all!(PinCursor<Vec<u8>>: Read, BufRead, Write, Seek);
+        assert_impl_all!(PinCursor<[u8; 4]>: Read, BufRead, Write, Seek);
+    }
+
+    #[test]
+    fn trait_matrix() {
+        assert_impl_all!(PinCursor<&'static mut Vec<u8>>: Read, BufRead, Write, Seek);
+        assert_impl_all!(PinCursor<&'static mut [u8]>: Read, BufRead, Write, Seek);
+        assert_impl_all!(PinCursor<&'static [u8]>: Read, BufRead, Seek);
+        assert_not_impl_any!(PinCursor<&'static [u8]>: Write, Unpin);
+        assert_impl_all!(PinCursor<Box<[u8]>>: Read, BufRead, Seek);
+        assert_not_impl_any!(PinCursor<Box<[u8]>>: Write);
+        assert_impl_all!(ReadHalf<&'static [u8]>: Read, Seek);
+        assert_not_impl_any!(WriteHalf<&'static [u8]>: Write);
+        assert_impl_all!(WindowCursor<&'static [u8]>: Read, Seek);
+        assert_impl_all!(LimitCursor<&'static [u8]>: Read, BufRead);
+    }
+
+    #[test]
+    fn display_summary() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"secret data".to_vec())).with_read_schedule(vec![2]));
+        let mut buf = [0u8; 4];
+        block_on(async {
+            cursor.as_mut().read(&mut buf).await.unwrap();
+            cursor.as_mut().read(&mut buf).await.unwrap();
+        });
+        let summary = cursor.to_string();
+        assert_eq!(summary, "PinCursor(pos=6/11, read=6B/2calls, write=0B/0calls)");
+        assert!(!summary.contains("secret"));
+    }
+
+    #[test]
+    fn escape_from_pin() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2])));
+        block_on(cursor.as_mut().seek(SeekFrom::End(0))).unwrap();
+        block_on(cursor.as_mut().write(&[3])).unwrap();
+        let c = cursor.unwrap_boxed();
+        assert_eq!((c.get_ref().as_slice(), c.position()), (&[1, 2, 3][..], 3));
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![4u8])));
+        block_on(cursor.as_mut().write(&[5, 6])).unwrap();
+        assert_eq!(cursor.into_data(), [5, 6]);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        block_on(async {
+            cursor.as_mut().write_all(b"session").await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(3)).await.unwrap();
+            cursor.as_mut().write_all(b"S").await.unwrap();
+        });
+        assert_eq!(cursor.into_inner_and_position(), (b"sesSion".to_vec(), 4));
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![7u8, 8])));
+        assert_eq!(cursor.as_mut().take_buffer(), [7, 8]);
+        assert!(cursor.is_empty());
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn generic_construction() {
+        fn pinned<T>(data: T) -> Pin<Box<PinCursor<T>>> {
+            Box::pin(PinCursor::wrap(Cursor::new(data)))
+        }
+
+        let mut cursor = pinned(&b"abc"[..]);
+        assert_eq!(block_on(cursor.as_mut().collect_all()).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn try_wrap_read_only() {
+        let cursor = PinCursor::try_wrap(Cursor::new(&b"abc"[..])).unwrap();
+        assert_impl_all!(PinCursor<&[u8]>: Read, BufRead, Seek);
+        assert_not_impl_any!(PinCursor<&[u8]>: Write);
+        let mut cursor = Box::pin(cursor);
+        let mut buf = [0u8; 3];
+        block_on(cursor.as_mut().read(&mut buf)).unwrap();
+        assert_eq!(&buf, b"abc");
+    }
+
+    #[test]
+    fn not_unpin_backing() {
+        struct Arena {
+            bytes: Vec<u8>,
+            _p: PhantomPinned,
+        }
+
+        impl AsRef<[u8]> for Arena {
+            fn as_ref(&self) -> &[u8] {
+                &self.bytes
+            }
+        }
+
+        impl Buffer for Arena {
+            fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<usize> {
+                self.bytes.write_at(pos, buf)
+            }
+        }
+
+        assert_not_impl_any!(Arena: Unpin);
+        assert_impl_all!(PinCursor<Arena>: Read, BufRead, Write, Seek);
+
+        let arena = Arena { bytes: b"head".to_vec(), _p: PhantomPinned };
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(arena)));
+        let mut line = String::new();
+        block_on(async {
+            cursor.as_mut().seek(SeekFrom::End(0)).await.unwrap();
+            cursor.as_mut().write_all(b"er\nbody").await.unwrap();
+            cursor.as_mut().set_position(0);
+            cursor.as_mut().read_line(&mut line).await.unwrap();
+        });
+        assert_eq!(line, "header\n");
+        assert_eq!(block_on(cursor.as_mut().collect_all()).unwrap(), b"body");
+        assert_eq!(cursor.into_data().bytes, b"header\nbody");
+    }
+
+    #[test]
+    fn marks() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        block_on(async {
+            cursor.as_mut().write_all(b"MAGIC").await.unwrap();
+            cursor.as_mut().mark("length");
+            cursor.as_mut().write_all(&[0, 0]).await.unwrap();
+            cursor.as_mut().write_all(b"body of the file").await.unwrap();
+            let body_len = cursor.position() - 7;
+            assert_eq!(cursor.as_mut().seek_to_mark("length").unwrap(), 5);
+            cursor.as_mut().write_all(&(body_len as u16).to_be_bytes()).await.unwrap();
+        });
+        assert_eq!(&cursor.bytes_ref()[..8], b"MAGIC\0\x10b");
+        assert_eq!(cursor.position(), 7);
+        assert_eq!(cursor.as_mut().seek_to_mark("checksum").unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(cursor.position(), 7);
+    }
+
+    #[test]
+    fn read_only_backing() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(&b"abc"[..])));
+        let mut buf = [0u8; 2];
+        block_on(async {
+            cursor.as_mut().seek(SeekFrom::Start(1)).await.unwrap();
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 2);
+        });
+        assert_eq!(&buf, b"bc");
+        assert_eq!(cursor.position(), 3);
+        assert_eq!(*cursor.get_ref(), b"abc");
+    }
+
+    #[test]
+    fn read_lines() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"one\ntwo\nthree".to_vec())));
+        let mut line = String::new();
+        block_on(async {
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 4);
+            assert_eq!(line, "one\n");
+            line.clear();
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 4);
+            assert_eq!(line, "two\n");
+            line.clear();
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 5);
+            assert_eq!(line, "three");
+            line.clear();
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 0);
+            assert_eq!(line, "");
+        });
+    }
+
+    #[test]
+    fn read_line_terminated_final_line() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"last\n".to_vec())).with_read_schedule(vec![1, 2]));
+        let mut line = String::new();
+        block_on(async {
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 5);
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 0);
+        });
+        assert_eq!(line, "last\n");
+    }
+
+    #[test]
+    fn read_until_delimiter() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 0, 3, 4])).with_read_schedule(vec![1, 1]));
+        let mut record = Vec::new();
+        block_on(async {
+            assert_eq!(cursor.as_mut().read_until(0, &mut record).await.unwrap(), 3);
+            assert_eq!(record, [1, 2, 0]);
+            record.clear();
+            // No delimiter left: reads to the end.
+            assert_eq!(cursor.as_mut().read_until(0, &mut record).await.unwrap(), 2);
+            assert_eq!(record, [3, 4]);
+            assert_eq!(cursor.as_mut().read_until(0, &mut record).await.unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn read_line_invalid_utf8() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"ab\xffcd\nok\n".to_vec())));
+        let mut line = String::from("kept");
+        let err = block_on(cursor.as_mut().read_line(&mut line)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(line, "kept");
+    }
+
+    #[test]
+    fn interrupted_reads() {
+        let data: Vec<u8> = (0..20).collect();
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(data.clone()))
+            .with_read_schedule(vec![3; 10])
+            .with_interrupt_every(2));
+        let mut buf = [0u8; 3];
+        let mut out = Vec::new();
+        let mut interrupts = 0;
+        block_on(async {
+            loop {
+                match cursor.as_mut().read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(n) => out.extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == ErrorKind::Interrupted => interrupts += 1,
+                    Err(e) => panic!("unexpected error: {}", e),
+                }
+            }
+        });
+        assert_eq!(out, data);
+        assert_eq!(interrupts, 7);
+        assert_eq!(cursor.position(), 20);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(data.clone())).with_interrupt_every(1));
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap_err().kind(), ErrorKind::Interrupted);
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn combined_read_faults() {
+        let cursor = PinCursor::wrap(Cursor::new(vec![0u8; 16]))
+            .with_read_schedule(vec![4, 4])
+            .fail_read_after(5);
+        let mut cursor = Box::pin(cursor);
+        let mut buf = [0u8; 8];
+        block_on(async {
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 4);
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap_err().kind(), ErrorKind::Other);
+        });
+        assert_eq!(cursor.bytes_read(), 4);
+        assert_eq!(cursor.position(), 4);
+    }
+
+    #[test]
+    fn stalls_come_after_errors() {
+        let cursor = PinCursor::wrap(Cursor::new(vec![0u8; 16]))
+            .fail_write_after(2)
+            .stall_writes(1);
+        let mut cursor = Box::pin(cursor);
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(cursor.as_mut().poll_write(&mut cx, &[1, 2]).is_pending());
+        assert!(matches!(cursor.as_mut().poll_write(&mut cx, &[1, 2]), Poll::Ready(Ok(2))));
+        assert!(matches!(cursor.as_mut().poll_write(&mut cx, &[3]), Poll::Ready(Err(_))));
+        assert_eq!(cursor.bytes_written(), 2);
+    }
+
+    #[test]
+    fn call_counters() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3, 4, 5])).with_read_schedule(vec![1; 5]));
+        let mut out = Vec::new();
+        assert_eq!(block_on(cursor.read_to_end(&mut out)).unwrap(), 5);
+        assert_eq!(cursor.read_calls(), 5);
+        assert_eq!(cursor.bytes_read(), 5);
+
+        cursor.as_mut().set_position(0);
+        block_on(cursor.write_all(&[0, 0])).unwrap();
+        assert_eq!(block_on(cursor.as_mut().write(&[])).unwrap(), 0);
+        assert_eq!(cursor.write_calls(), 1);
+    }
+
+    #[test]
+    fn reset_counters() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new((0u8..10).collect::<Vec<_>>())).fail_read_after(8));
+        let mut buf = [0u8; 4];
+        block_on(cursor.as_mut().read_exact(&mut buf)).unwrap();
+        block_on(cursor.as_mut().write(&[0])).unwrap();
+        cursor.as_mut().reset_counters();
+        assert_eq!(cursor.stats(), Stats::default());
+        assert_eq!(cursor.position(), 5);
+
+        block_on(cursor.as_mut().read_exact(&mut buf[..3])).unwrap();
+        assert_eq!(&buf[..3], [5, 6, 7]);
+        assert_eq!(cursor.stats(), Stats { bytes_read: 3, bytes_written: 0, read_calls: 1, write_calls: 0 });
+        // 7 bytes have been read in total, so the limit of 8 still stands in the way.
+        assert!(block_on(cursor.as_mut().read_exact(&mut buf[..2])).is_err());
+    }
+
+    #[test]
+    fn into_parts() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        let mut buf = [0u8; 2];
+        block_on(async {
+            cursor.as_mut().write(&[1, 2, 3]).await.unwrap();
+            cursor.as_mut().write(&[4]).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(1)).await.unwrap();
+            cursor.as_mut().read(&mut buf).await.unwrap();
+        });
+        let (data, pos, stats) = cursor.into_parts();
+        assert_eq!(data, [1, 2, 3, 4]);
+        assert_eq!(pos, 3);
+        assert_eq!(stats, Stats { bytes_read: 2, bytes_written: 4, read_calls: 1, write_calls: 2 });
+    }
+
+    #[test]
+    fn boxed_futures() {
+        let mut source = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])));
+        let mut sink = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        let mut fixed = Box::pin(PinCursor::from_array([0u8; 2]));
+        let mut buf = [0u8; 2];
+        let actions: Vec<Pin<Box<dyn Future<Output=Result<usize>>>>> = vec![
+            source.as_mut().read_boxed(&mut buf),
+            sink.as_mut().write_boxed(&[4, 5, 6]),
+            fixed.as_mut().write_boxed(&[7, 8, 9]),
+        ];
+        let results: Vec<usize> = block_on(async {
+            let mut results = Vec::new();
+            for action in actions {
+                results.push(action.await.unwrap());
+            }
+            results
+        });
+        assert_eq!(results, [2, 3, 2]);
+        assert_eq!(buf, [1, 2]);
+        assert_eq!(block_on(sink.as_mut().seek_boxed(SeekFrom::Start(1))).unwrap(), 1);
+    }
+
+    #[test]
+    fn bytes_mut() {
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 4]));
+        cursor.as_mut().bytes_mut().copy_from_slice(&[4, 3, 2, 1]);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.bytes_written(), 0);
+        let mut buf = [0u8; 4];
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 4);
+        assert_eq!(buf, [4, 3, 2, 1]);
+        assert_eq!(cursor.bytes_ref(), [4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut cursor = PinCursor::wrap(Cursor::new(vec![0u8]));
+        cursor.extend(1..4);
+        cursor.extend(&[4, 5]);
+        assert_eq!(cursor.len(), 6);
+        assert_eq!(cursor.position(), 0);
+        let mut cursor = Box::pin(cursor);
+        let mut buf = [0u8; 6];
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 6);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn op_log_resolves_seeks() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 10])).with_op_log());
+        block_on(async {
+            cursor.as_mut().seek(SeekFrom::Start(5)).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Current(-2)).await.unwrap();
+            cursor.as_mut().write(&[1, 2]).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Current(2)).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::End(-3)).await.unwrap();
+        });
+        assert_eq!(cursor.op_log(), [
+            Op::Seek { target: SeekFrom::Start(5), pos: 5 },
+            Op::Seek { target: SeekFrom::Current(-2), pos: 3 },
+            Op::Write { pos: 3, len: 2 },
+            Op::Seek { target: SeekFrom::Current(2), pos: 7 },
+            Op::Seek { target: SeekFrom::End(-3), pos: 7 },
+        ]);
+    }
+
+    #[test]
+    fn spin_read_without_executor() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])).stall_reads(5));
+        let mut buf = [0u8; 2];
+        assert_eq!(cursor.as_mut().spin_read(&mut buf).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+
+        let mut gated = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8])).stall_reads(SPIN_LIMIT));
+        assert_eq!(gated.as_mut().spin_read(&mut buf).unwrap_err().kind(), ErrorKind::TimedOut);
+        assert_eq!(gated.position(), 0);
+    }
+
+    #[test]
+    fn error_after_close() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())).with_error_after_close(ErrorKind::BrokenPipe));
+        block_on(async {
+            cursor.write_all(b"ok").await.unwrap();
+            poll_fn(|cx| cursor.as_mut().poll_close(cx)).await.unwrap();
+            poll_fn(|cx| cursor.as_mut().poll_close(cx)).await.unwrap();
+            assert_eq!(cursor.write(b"late").await.unwrap_err().kind(), ErrorKind::BrokenPipe);
+        });
+        assert_eq!(cursor.bytes_ref(), b"ok");
+
+        let mut permissive = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        block_on(async {
+            poll_fn(|cx| permissive.as_mut().poll_close(cx)).await.unwrap();
+            permissive.write_all(b"late").await.unwrap();
+        });
+        assert_eq!(permissive.bytes_ref(), b"late");
+    }
+
+    #[test]
+    fn seek_errors() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 4])));
+        block_on(async {
+            let err = cursor.as_mut().seek(SeekFrom::Current(-1)).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            assert!(err.to_string().contains("-1"), "{}", err);
+
+            cursor.as_mut().set_position(2);
+            let err = cursor.as_mut().seek(SeekFrom::End(-5)).await.unwrap_err();
+            assert!(err.to_string().contains("-1"), "{}", err);
+            let err = cursor.as_mut().seek(SeekFrom::Current(i64::MIN)).await.unwrap_err();
+            assert!(err.to_string().contains("-9223372036854775806"), "{}", err);
+            assert_eq!(cursor.position(), 2);
+
+            cursor.as_mut().set_position(u64::MAX);
+            assert!(cursor.as_mut().seek(SeekFrom::Current(1)).await.is_err());
+            assert_eq!(cursor.as_mut().seek(SeekFrom::Current(-1)).await.unwrap(), u64::MAX - 1);
+        });
+    }
+
+    #[test]
+    fn position_policies() {
+        fn check<T: Buffer>(data: T, policy: PositionPolicy, expected: Option<u64>) {
+            let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(data)).position_policy(policy));
+            let seek = block_on(cursor.as_mut().seek(SeekFrom::Start(10)));
+            assert_eq!(seek.ok(), expected, "{:?}", policy);
+            assert_eq!(cursor.position(), expected.unwrap_or(0), "{:?}", policy);
+            assert_eq!(block_on(cursor.as_mut().seek(SeekFrom::End(0))).unwrap(), 4);
+
+            if let Some(expected) = expected {
+                cursor.as_mut().set_position(10);
+                assert_eq!(cursor.position(), expected, "{:?}", policy);
+            }
+            assert_eq!(cursor.as_mut().checked_set_position(10).unwrap_err().kind(), ErrorKind::InvalidInput);
+            cursor.as_mut().checked_set_position(4).unwrap();
+            assert_eq!(cursor.position(), 4);
+        }
+
+        let table = [
+            (PositionPolicy::Lenient, Some(10)),
+            (PositionPolicy::Clamp, Some(4)),
+            (PositionPolicy::Strict, None),
+        ];
+        for (policy, expected) in table {
+            check(vec![0u8; 4], policy, expected);
+            let mut array = [0u8; 4];
+            check(&mut array[..], policy, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end")]
+    fn strict_set_position_panics() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 4])).position_policy(PositionPolicy::Strict));
+        cursor.as_mut().set_position(5);
+    }
+
+    #[test]
+    fn gap_policies() {
+        for policy in [GapPolicy::ZeroFill, GapPolicy::Error, GapPolicy::Sparse] {
+            let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])).gap_policy(policy));
+            block_on(cursor.as_mut().seek(SeekFrom::End(100))).unwrap();
+            let result = block_on(cursor.as_mut().write(&[4]));
+            if policy == GapPolicy::Error {
+                let err = result.unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::InvalidInput);
+                assert!(err.to_string().contains("gap of 100 bytes"), "{}", err);
+                assert_eq!((cursor.len(), cursor.position()), (3, 103));
+            } else {
+                // A contiguous buffer cannot leave a hole, so `Sparse` zero-fills here.
+                assert_eq!(result.unwrap(), 1);
+                assert_eq!(cursor.len(), 104);
+                assert!(cursor.bytes_ref()[3..103].iter().all(|&b| b == 0));
+            }
+            block_on(cursor.as_mut().seek(SeekFrom::End(0))).unwrap();
+            assert_eq!(block_on(cursor.as_mut().write(&[5])).unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn fork_is_independent() {
+        let mut data = [1u8, 2, 3, 4];
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(&mut data[..])));
+        cursor.as_mut().set_position(2);
+        let mut fork = Box::pin(cursor.fork());
+        assert_eq!((fork.bytes_ref(), fork.position()), (&[1, 2, 3, 4][..], 2));
+
+        block_on(fork.as_mut().write(&[9, 9, 9])).unwrap();
+        assert_eq!(fork.bytes_ref(), [1, 2, 9, 9, 9]);
+        assert_eq!((cursor.bytes_ref(), cursor.position()), (&[1, 2, 3, 4][..], 2));
+        drop(cursor);
+        assert_eq!(data, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn position_overflow() {
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 4]));
+        block_on(async {
+            cursor.as_mut().seek(SeekFrom::Start(u64::MAX - 1)).await.unwrap();
+            let err = cursor.as_mut().write(&[1, 2, 3, 4]).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            assert!(err.to_string().contains("overflow"), "{}", err);
+            assert_eq!(cursor.position(), u64::MAX - 1);
+
+            let err = cursor.as_mut().seek(SeekFrom::Current(2)).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            assert!(err.to_string().contains("overflow"), "{}", err);
+            assert_eq!(cursor.position(), u64::MAX - 1);
+        });
+        assert_eq!(cursor.bytes_written(), 0);
+    }
+
+    #[test]
+    fn clamping_seeks() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 4])).clamping_seeks());
+        block_on(async {
+            assert_eq!(cursor.as_mut().seek(SeekFrom::Current(-1)).await.unwrap(), 0);
+            assert_eq!(cursor.as_mut().seek(SeekFrom::End(-5)).await.unwrap(), 0);
+            assert_eq!(cursor.as_mut().seek(SeekFrom::Current(i64::MIN)).await.unwrap(), 0);
+            assert_eq!(cursor.as_mut().seek(SeekFrom::Start(9)).await.unwrap(), 4);
+            assert_eq!(cursor.as_mut().seek(SeekFrom::Current(i64::MAX)).await.unwrap(), 4);
+        });
+    }
+
+    #[test]
+    fn reserve_avoids_reallocation() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        cursor.as_mut().reserve(64);
+        let capacity = cursor.get_ref().capacity();
+        let ptr = cursor.get_ref().as_ptr();
+        assert!(capacity >= 64);
+        block_on(async {
+            for _ in 0..8 {
+                cursor.as_mut().write(&[1u8; 8]).await.unwrap();
+            }
+        });
+        assert_eq!(cursor.len(), 64);
+        assert_eq!((cursor.get_ref().capacity(), cursor.get_ref().as_ptr()), (capacity, ptr));
+    }
+
+    #[test]
+    fn virtual_clock() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 10])).with_clock(|| Duration::from_millis(5)));
+        let mut buf = [0u8; 2];
+        block_on(async {
+            for _ in 0..3 {
+                cursor.as_mut().read(&mut buf).await.unwrap();
+            }
+        });
+        assert_eq!(cursor.elapsed(), Duration::from_millis(15));
+        assert_eq!(PinCursor::wrap(Cursor::new(vec![0u8])).elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn strict_close() {
+        let close = |cursor: &mut Pin<Box<PinCursor<Vec<u8>>>>| block_on(poll_fn(|cx| cursor.as_mut().poll_close(cx)));
+        let flush = |cursor: &mut Pin<Box<PinCursor<Vec<u8>>>>| block_on(poll_fn(|cx| cursor.as_mut().poll_flush(cx)));
+        let mut buf = [0u8; 2];
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"abcd".to_vec()))
+            .with_strict_close(ReadsAfterClose::Eof)
+            .with_op_log()
+            .with_clock(|| Duration::from_millis(1)));
+        assert!(!cursor.is_closed());
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 2);
+        assert_eq!(block_on(cursor.as_mut().write(b"X")).unwrap(), 1);
+        flush(&mut cursor).unwrap();
+        close(&mut cursor).unwrap();
+        assert!(cursor.is_closed());
+        assert_eq!(block_on(cursor.as_mut().write(b"Y")).unwrap_err().kind(), ErrorKind::BrokenPipe);
+        assert_eq!(flush(&mut cursor).unwrap_err().kind(), ErrorKind::BrokenPipe);
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 0);
+        let elapsed = cursor.elapsed();
+        close(&mut cursor).unwrap();
+        assert_eq!(cursor.stats(), Stats { bytes_read: 2, bytes_written: 1, read_calls: 1, write_calls: 1 });
+        assert_eq!(cursor.op_log().iter().filter(|op| **op == Op::Close).count(), 1);
+        assert_eq!(cursor.elapsed(), elapsed);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"abcd".to_vec())).with_strict_close(ReadsAfterClose::Allowed));
+        close(&mut cursor).unwrap();
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 2);
+        assert!(block_on(cursor.as_mut().write(b"Y")).is_err());
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new()))
+            .with_strict_close(ReadsAfterClose::Allowed)
+            .with_error_after_close(ErrorKind::NotConnected));
+        close(&mut cursor).unwrap();
+        assert_eq!(flush(&mut cursor).unwrap_err().kind(), ErrorKind::NotConnected);
+        assert_eq!(block_on(cursor.as_mut().write(b"Y")).unwrap_err().kind(), ErrorKind::NotConnected);
+    }
+
+    #[test]
+    fn expect_closed_satisfied() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())).expect_closed());
+        block_on(poll_fn(|cx| cursor.as_mut().poll_close(cx))).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "without being closed")]
+    fn expect_closed_violated() {
+        let _cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::<u8>::new())).expect_closed());
+    }
+
+    #[test]
+    fn replay_trace() {
+        let trace = "
+            # captured from a flaky peer
+            read 3
+            write 2
+            read 1
+            read 4
+            read error
+        ";
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![7u8; 16])).with_replay(trace).unwrap());
+        let mut buf = [0u8; 8];
+        let mut sizes = Vec::new();
+        block_on(async {
+            for _ in 0..3 {
+                sizes.push(cursor.as_mut().read(&mut buf).await.unwrap());
+            }
+            assert!(cursor.as_mut().read(&mut buf).await.is_err());
+            assert_eq!(cursor.as_mut().write(&[1, 2, 3]).await.unwrap(), 2);
+        });
+        assert_eq!(sizes, [3, 1, 4]);
+
+        for bad in ["read", "seek 3", "read three", "write error\nwrite 1"] {
+            let err = PinCursor::wrap(Cursor::new(Vec::<u8>::new())).with_replay(bad).err().unwrap();
+            assert_eq!(err.kind(), ErrorKind::InvalidData, "{}", bad);
+        }
+    }
+
+    // Does not stop on `Ok(0)`, so it would spin forever on a full buffer; bounded here.
+    fn buggy_write_loop(mut cursor: Pin<&mut PinCursor<[u8; 4]>>) -> Result<()> {
+        let mut written = 0;
+        for _ in 0..1000 {
+            if written >= 6 {
+                return Ok(());
+            }
+            written += block_on(cursor.as_mut().write(&[1, 2]))?;
+        }
+        panic!("the write loop did not fail fast");
+    }
+
+    #[test]
+    fn error_on_full() {
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 4]).error_on_full());
+        let err = buggy_write_loop(cursor.as_mut()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(err.to_string().contains("write of 2 bytes at 4 does not fit into a buffer of 4 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn strict_capacity() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"abc".to_vec())).with_strict_capacity(6));
+        block_on(cursor.as_mut().seek(SeekFrom::End(0))).unwrap();
+        let err = block_on(cursor.as_mut().write(b"12345")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(err.to_string().contains("write of 5 bytes at 3 would exceed the capacity of 6 bytes"), "{}", err);
+        assert_eq!((cursor.bytes_ref(), cursor.position(), cursor.bytes_written()), (&b"abc"[..], 3, 0));
+
+        assert_eq!(block_on(cursor.as_mut().write(b"123")).unwrap(), 3);
+        cursor.as_mut().set_position(1);
+        assert_eq!(block_on(cursor.as_mut().write(b"BC")).unwrap(), 2);
+        assert_eq!(cursor.bytes_ref(), b"aBC123");
+    }
+
+    #[test]
+    #[should_panic(expected = "11 writes in a row returned Ok(0)")]
+    fn max_zero_writes() {
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 4]).max_zero_writes(10));
+        let _ = buggy_write_loop(cursor.as_mut());
+    }
+
+    #[test]
+    fn op_log_filters() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 10])).with_op_log());
+        let mut buf = [0u8; 2];
+        block_on(async {
+            cursor.as_mut().write(&[1, 2]).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(1)).await.unwrap();
+            cursor.as_mut().read(&mut buf).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::End(-1)).await.unwrap();
+            cursor.as_mut().read(&mut buf).await.unwrap();
+        });
+        assert_eq!(cursor.ops().count(), 5);
+        let targets: Vec<_> = cursor.seeks().map(|op| match op {
+            Op::Seek { pos, .. } => *pos,
+            _ => unreachable!(),
+        }).collect();
+        assert_eq!(targets, [1, 9]);
+        assert_eq!(cursor.reads().count(), 2);
+        assert!(cursor.writes().eq([&Op::Write { pos: 0, len: 2 }]));
+    }
+
+    #[test]
+    fn bytes_in_range() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 10])).with_op_log());
+        let mut buf = [0u8; 4];
+        block_on(async {
+            cursor.as_mut().write(&[1, 2, 3]).await.unwrap();
+            cursor.as_mut().read(&mut buf[..1]).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(0)).await.unwrap();
+            cursor.as_mut().read(&mut buf).await.unwrap();
+            cursor.as_mut().write(&[4, 5]).await.unwrap();
+            cursor.as_mut().read(&mut buf[..2]).await.unwrap();
+        });
+        assert_eq!(cursor.bytes_in_range(1..5), (5, 2));
+        assert_eq!(cursor.bytes_in_range(0..1), (0, 3));
+        assert_eq!(cursor.bytes_in_range(2..3), (0, 0));
+        assert_eq!(cursor.bytes_in_range(4..100), (2, 2));
+        assert_eq!(cursor.bytes_in_range(0..6), (cursor.bytes_read(), cursor.bytes_written()));
+        assert_eq!(cursor.bytes_in_range(8..10), (0, 0));
+    }
+
+    #[test]
+    fn named_futures() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])));
+        let mut buf = [0u8; 2];
+        let read: ReadFuture<'_, '_, Vec<u8>> = cursor.as_mut().read(&mut buf);
+        assert_eq!(block_on(read).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+        let seek: SeekFuture<'_, Vec<u8>> = cursor.as_mut().seek(SeekFrom::End(0));
+        assert_eq!(block_on(seek).unwrap(), 3);
+        let write: WriteFuture<'_, '_, Vec<u8>> = cursor.as_mut().write(&[4]);
+        assert_eq!(block_on(write).unwrap(), 1);
+    }
+
+    #[test]
+    fn named_futures_in_state_machine() {
+        // A hand-written future that stores the in-flight read and counts how often it is polled.
+        struct CountPolls<'a> {
+            read: ReadFuture<'a, 'a, Vec<u8>>,
+            polls: u32,
+        }
+
+        impl Future for CountPolls<'_> {
+            type Output = (Result<usize>, u32);
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                let this = self.get_mut();
+                this.polls += 1;
+                let n = ready!(Pin::new(&mut this.read).poll(cx));
+                Poll::Ready((n, this.polls))
+            }
+        }
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])).stall_reads(2));
+        let mut buf = [0u8; 2];
+        let mut counter = CountPolls { read: cursor.as_mut().read(&mut buf), polls: 0 };
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(Pin::new(&mut counter).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut counter).poll(&mut cx).is_pending());
+        let Poll::Ready((n, polls)) = Pin::new(&mut counter).poll(&mut cx) else { panic!("read is still stalled") };
+        assert_eq!((n.unwrap(), polls), (2, 3));
+        drop(counter);
+        assert_eq!(buf, [1, 2]);
+
+        let mut line = String::new();
+        let read_line: ReadLineFuture<'_, '_, Vec<u8>> = cursor.as_mut().read_line(&mut line);
+        assert_eq!(block_on(read_line).unwrap(), 1);
+        let collect: CollectAllFuture<'_, Vec<u8>> = cursor.as_mut().collect_all();
+        assert_eq!(block_on(collect).unwrap(), []);
+    }
+
+    #[test]
+    fn independent_buffer_lifetimes() {
+        static FIXTURE: &[u8] = b"fixture";
+
+        // The futures name the cursor borrow and the buffer separately...
+        fn write_fixture(cursor: Pin<&mut PinCursor<Vec<u8>>>) -> WriteFuture<'_, 'static, Vec<u8>> {
+            cursor.write(FIXTURE)
+        }
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        block_on(write_fixture(cursor.as_mut())).unwrap();
+
+        // ...so results that borrow from the buffer leave the cursor free to be used again.
+        cursor.as_mut().set_position(0);
+        let mut uninit = [MaybeUninit::uninit(); 3];
+        let read = block_on(cursor.as_mut().read_uninit(&mut uninit)).unwrap();
+        assert_eq!(cursor.position(), 3);
+        let mut rest = [0u8; 4];
+        block_on(cursor.as_mut().read(&mut rest)).unwrap();
+        assert_eq!(read, b"fix");
+        assert_eq!(&rest, b"ture");
+    }
+
+    #[test]
+    fn pinned_futures() {
+        assert_impl_all!(ReadFuture<'static, 'static, Vec<u8>>: Unpin);
+        assert_impl_all!(WriteFuture<'static, 'static, Vec<u8>>: Unpin);
+        assert_impl_all!(SeekFuture<'static, Vec<u8>>: Unpin);
+        assert_not_impl_all!(Pinned<ReadFuture<'static, 'static, Vec<u8>>>: Unpin);
+        assert_not_impl_all!(Pinned<WriteFuture<'static, 'static, Vec<u8>>>: Unpin);
+        assert_not_impl_all!(Pinned<SeekFuture<'static, Vec<u8>>>: Unpin);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])));
+        let mut buf = [0u8; 2];
+        assert_eq!(block_on(cursor.as_mut().read_pinned(&mut buf)).unwrap(), 2);
+        assert_eq!(block_on(cursor.as_mut().seek_pinned(SeekFrom::End(0))).unwrap(), 3);
+        assert_eq!(block_on(cursor.as_mut().write_pinned(&[4])).unwrap(), 1);
+        assert_eq!(cursor.bytes_ref(), [1, 2, 3, 4]);
+    }
+
+    // Polls `future` once, then drops it.
+    fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+        std::pin::pin!(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn cancel_safe_single_polls() {
+        let cursor = PinCursor::wrap(Cursor::new(b"abcdef".to_vec()))
+            .with_read_schedule(vec![1])
+            .stall_reads(1)
+            .with_write_schedule(vec![2])
+            .stall_writes(1);
+        let mut cursor = Box::pin(cursor);
+        let mut buf = [0u8; 4];
+
+        assert!(poll_once(cursor.as_mut().read(&mut buf)).is_pending());
+        let mut uninit = [MaybeUninit::<u8>::uninit(); 4];
+        assert!(poll_once(cursor.as_mut().read_uninit(&mut uninit)).is_pending());
+        assert!(poll_once(cursor.as_mut().write(b"XYZ")).is_pending());
+        assert_eq!((cursor.position(), cursor.stats()), (0, Stats::default()));
+        assert_eq!(cursor.bytes_ref(), b"abcdef");
+
+        // Both stalls and schedule entries are still there for the next operations.
+        assert!(poll_once(cursor.as_mut().read(&mut buf)).is_pending());
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 1);
+        assert!(poll_once(cursor.as_mut().write(b"XYZ")).is_pending());
+        assert_eq!(block_on(cursor.as_mut().write(b"XYZ")).unwrap(), 2);
+        assert_eq!(cursor.bytes_ref(), b"aXYdef");
+
+        // Seeks never stall, so there is nothing to drop halfway.
+        assert!(matches!(poll_once(cursor.as_mut().seek(SeekFrom::Start(1))), Poll::Ready(Ok(1))));
+    }
+
+    #[test]
+    fn multi_poll_cancellation() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let new_cursor = || Box::pin(PinCursor::wrap(Cursor::new(b"abcd\nef".to_vec())).with_read_schedule(vec![2]).stall_reads(1));
+
+        // The first poll stalls, the second reads a chunk and then stalls again.
+        let mut cursor = new_cursor();
+        let mut until = Vec::new();
+        {
+            let mut read_until = std::pin::pin!(cursor.as_mut().read_until(b'\n', &mut until));
+            assert!(read_until.as_mut().poll(&mut cx).is_pending());
+            assert!(read_until.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!((until.as_slice(), cursor.position()), (&b"ab"[..], 2));
+        let mut line = String::new();
+        assert!(poll_once(cursor.as_mut().read_line(&mut line)).is_pending());
+        assert_eq!(block_on(cursor.as_mut().read_line(&mut line)).unwrap(), 3);
+        assert_eq!(line, "cd\n");
+
+        let mut cursor = new_cursor();
+        {
+            let mut read_line = std::pin::pin!(cursor.as_mut().read_line(&mut line));
+            assert!(read_line.as_mut().poll(&mut cx).is_pending());
+            assert!(read_line.as_mut().poll(&mut cx).is_pending());
+        }
+        // Not cancel-safe: "ab" is gone.
+        assert_eq!((line.as_str(), cursor.position()), ("cd\n", 2));
+
+        let mut cursor = new_cursor();
+        {
+            let mut collect = std::pin::pin!(cursor.as_mut().collect_all());
+            assert!(collect.as_mut().poll(&mut cx).is_pending());
+            assert!(collect.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!(cursor.position(), 2);
+        assert!(poll_once(cursor.as_mut().collect_all()).is_pending());
+        assert_eq!(block_on(cursor.as_mut().collect_all()).unwrap(), b"cd\nef");
+    }
+
+    #[test]
+    fn polling_pinned_futures() {
+        assert_not_impl_all!(Pinned<ReadLineFuture<'static, 'static, Vec<u8>>>: Unpin);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"ab\ncd".to_vec())).stall_reads(1));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 2];
+        {
+            let mut read = Box::pin(cursor.as_mut().read_pinned(&mut buf));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            assert!(matches!(read.as_mut().poll(&mut cx), Poll::Ready(Ok(2))));
+        }
+        assert_eq!(&buf, b"ab");
+
+        let mut line = String::new();
+        {
+            let mut read_line = std::pin::pin!(Pinned::new(cursor.as_mut().read_line(&mut line)));
+            assert!(read_line.as_mut().poll(&mut cx).is_pending());
+            assert!(matches!(read_line.as_mut().poll(&mut cx), Poll::Ready(Ok(1))));
+        }
+        assert_eq!(line, "\n");
+
+        let mut seek = std::pin::pin!(cursor.as_mut().seek_pinned(SeekFrom::Current(-1)));
+        assert!(matches!(seek.as_mut().poll(&mut cx), Poll::Ready(Ok(2))));
+    }
+
+    #[test]
+    fn extreme_position() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])));
+        cursor.as_mut().set_position(u64::MAX);
+        assert_eq!(cursor.position_checked_add(0), Some(u64::MAX));
+        assert_eq!(cursor.position_checked_add(1), None);
+        assert_eq!(cursor.filled(), [1, 2, 3]);
+        assert!(cursor.unread().is_empty());
+        assert_eq!(cursor.remaining(), 0);
+        assert_eq!(cursor.len(), 3);
+        assert_eq!(block_on(cursor.as_mut().read(&mut [0u8; 4])).unwrap(), 0);
+        assert!(block_on(cursor.as_mut().write(&[1])).is_err());
+        assert_eq!(cursor.position(), u64::MAX);
+
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 2]));
+        cursor.as_mut().set_position(u64::MAX);
+        assert_eq!(block_on(cursor.as_mut().write(&[1])).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    fn project_cursor() {
+        trait PollPeek {
+            fn poll_peek(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>>;
+        }
+
+        struct Peeker<'a, T>(Pin<&'a mut PinCursor<T>>);
+
+        impl<T> PollPeek for Peeker<'_, T>
+            where T: AsRef<[u8]> + Unpin
+        {
+            fn poll_peek(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+                let mut c = self.0.as_mut().project_cursor();
+                let pos = c.position();
+                let n = ready!(c.as_mut().poll_read(cx, buf))?;
+                c.set_position(pos);
+                Poll::Ready(Ok(n))
+            }
+        }
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"abcdef".to_vec())));
+        cursor.as_mut().set_position(2);
+        let mut buf = [0u8; 3];
+        let mut peeker = Peeker(cursor.as_mut());
+        assert_eq!(block_on(poll_fn(|cx| peeker.poll_peek(cx, &mut buf))).unwrap(), 3);
+        assert_eq!(&buf, b"cde");
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.stats().read_calls, 0);
+    }
+
+    #[test]
+    fn buffered_writes() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"ab".to_vec())).with_buffered_writes());
+        block_on(async {
+            cursor.seek(SeekFrom::End(0)).await.unwrap();
+            cursor.write_all(b"cd").await.unwrap();
+            cursor.write_all(b"ef").await.unwrap();
+        });
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(cursor.get_ref(), b"ab");
+        assert_eq!(cursor.filled(), b"ab");
+        let mut out = Vec::new();
+        cursor.as_mut().set_position(0);
+        block_on(cursor.read_to_end(&mut out)).unwrap();
+        assert_eq!(out, b"ab");
+
+        block_on(cursor.flush()).unwrap();
+        assert_eq!(cursor.get_ref(), b"abcdef");
+        cursor.as_mut().set_position(0);
+        out.clear();
+        block_on(cursor.read_to_end(&mut out)).unwrap();
+        assert_eq!(out, b"abcdef");
+
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 3]).with_buffered_writes());
+        block_on(cursor.write_all(b"xyzw")).unwrap();
+        assert_eq!(block_on(cursor.flush()).unwrap_err().kind(), ErrorKind::WriteZero);
+        assert_eq!(cursor.get_ref(), b"xyz");
+    }
+
+    #[test]
+    fn buffered_writes_count_toward_length() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new()))
+            .with_buffered_writes()
+            .gap_policy(GapPolicy::Error));
+        block_on(async {
+            cursor.write_all(b"ab").await.unwrap();
+            cursor.write_all(b"cd").await.unwrap();
+            cursor.flush().await.unwrap();
+        });
+        assert_eq!(cursor.get_ref(), b"abcd");
+        cursor.as_mut().set_position(6);
+        assert_eq!(block_on(cursor.write(b"x")).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new()))
+            .with_buffered_writes()
+            .with_loopback()
+            .with_strict_capacity(3));
+        block_on(cursor.write_all(b"abc")).unwrap();
+        assert_eq!(block_on(cursor.write_all(b"def")).unwrap_err().kind(), ErrorKind::WriteZero);
+        block_on(cursor.flush()).unwrap();
+        assert_eq!(cursor.len(), 3);
+    }
+
+    #[test]
+    fn unpin_twin() {
+        assert_not_impl_any!(PinCursor<Vec<u8>>: Unpin);
+        assert_impl_all!(UnpinCursor<Vec<u8>>: Unpin, Read, BufRead, Write, Seek, Send);
+
+        async fn scenario<C>(mut c: Pin<&mut C>) -> Result<Vec<u8>>
+            where C: BufRead + Write + Seek + ?Sized
+        {
+            c.as_mut().seek(SeekFrom::End(0)).await?;
+            c.as_mut().write_all(b"tail").await?;
+            c.as_mut().seek(SeekFrom::Start(2)).await?;
+            let mut line = Vec::new();
+            c.as_mut().read_until(b'\n', &mut line).await?;
+            let mut rest = Vec::new();
+            c.as_mut().read_to_end(&mut rest).await?;
+            line.extend(rest);
+            Ok(line)
+        }
+
+        let configure = || PinCursor::wrap(Cursor::new(b"ab\ncd\n".to_vec()))
+            .with_read_schedule(vec![3, 1])
+            .with_write_schedule(vec![2])
+            .stall_reads(1)
+            .with_op_log();
+        let mut pinned = Box::pin(configure());
+        let mut unpin = configure().into_unpin();
+        let from_pinned = block_on(scenario(pinned.as_mut())).unwrap();
+        let from_unpin = block_on(scenario(Pin::new(&mut unpin))).unwrap();
+        assert_eq!(from_pinned, b"\ncd\ntail");
+        assert_eq!(from_pinned, from_unpin);
+        assert_eq!(pinned.op_log(), unpin.op_log());
+        assert_eq!(pinned.stats(), unpin.stats());
+        assert_eq!(pinned.bytes_ref(), unpin.bytes_ref());
+
+        // The extension traits work on the unpinned cursor directly.
+        let mut buf = [0u8; 2];
+        unpin.set_position(0);
+        assert_eq!(block_on(unpin.read(&mut buf)).unwrap(), 2);
+        assert_eq!(unpin.position(), 2);
+
+        // So do the methods that need a pinned `PinCursor`.
+        unpin.mark("two");
+        unpin.reset_counters();
+        unpin.extend(b"!");
+        assert_eq!(unpin.seek_to_mark("two").unwrap(), 2);
+        assert_eq!(unpin.spin_read(&mut buf).unwrap(), 2);
+        assert_eq!(unpin.bytes_read(), 2);
+        unpin.bytes_mut()[0] = b'A';
+        unpin.reserve(16);
+        assert_eq!(unpin.take_buffer(), b"Ab\ncd\ntail!");
+        assert_eq!(unpin.position(), 0);
+    }
+
+    #[test]
+    fn wrap_past_end() {
+        let mut c = Cursor::new(b"abc".to_vec());
+        c.set_position(10);
+        let mut cursor = Box::pin(PinCursor::wrap(c));
+        assert_eq!(cursor.position(), 10);
+        assert_eq!((cursor.filled(), cursor.unread(), cursor.remaining()), (&b"abc"[..], &b""[..], 0));
+        assert!(cursor.at_end());
+        let mut line = String::new();
+        block_on(async {
+            assert_eq!(cursor.as_mut().read(&mut [0u8; 4]).await.unwrap(), 0);
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 0);
+            assert_eq!(cursor.as_mut().seek(SeekFrom::Current(0)).await.unwrap(), 10);
+        });
+        assert!(block_on(cursor.as_mut().collect_all()).unwrap().is_empty());
+
+        let mut std_cursor = std::io::Cursor::new(vec![1u8, 2]);
+        std_cursor.set_position(3);
+        let mut cursor = Box::pin(PinCursor::from(std_cursor));
+        assert!(cursor.at_end());
+        assert_eq!(cursor.remaining(), 0);
+        block_on(cursor.as_mut().write(&[4])).unwrap();
+        assert_eq!(cursor.bytes_ref(), [1, 2, 0, 4]);
+        assert!(cursor.at_end());
+        cursor.as_mut().set_position(1);
+        assert!(!cursor.at_end());
+    }
+
+    #[test]
+    fn loopback() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())).with_loopback());
+        let mut buf = [0u8; 8];
+        block_on(async {
+            assert_eq!(cursor.as_mut().write(b"request").await.unwrap(), 7);
+            assert_eq!(cursor.position(), 0);
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 7);
+            assert_eq!(&buf[..7], b"request");
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 0);
+            assert_eq!(cursor.as_mut().write(b"!").await.unwrap(), 1);
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 1);
+            assert_eq!(buf[0], b'!');
+        });
+    }
+
+    #[test]
+    fn append_writes() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"one,".to_vec())).with_append_writes());
+        let mut buf = [0u8; 3];
+        block_on(async {
+            cursor.as_mut().read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"one");
+            cursor.as_mut().write_all(b"two,").await.unwrap();
+            assert_eq!(cursor.position(), 3);
+            cursor.as_mut().read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b",tw");
+            cursor.as_mut().write_all(b"three").await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(0)).await.unwrap();
+            cursor.as_mut().write_all(b"!").await.unwrap();
+            assert_eq!(cursor.position(), 0);
+        });
+        assert_eq!(cursor.bytes_ref(), b"one,two,three!");
+    }
+
+    #[test]
+    fn null_sink() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())).with_null_sink().with_write_schedule(vec![10]));
+        let chunk = [7u8; 4096];
+        block_on(async {
+            assert_eq!(cursor.as_mut().write(&chunk).await.unwrap(), 10);
+            for _ in 0..256 {
+                cursor.write_all(&chunk).await.unwrap();
+            }
+        });
+        assert_eq!(cursor.bytes_written(), 1024 * 1024 + 10);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.get_ref().capacity(), 0);
+    }
+
+    #[test]
+    fn random_faults_are_reproducible() {
+        fn read_sizes(seed: u64) -> Vec<Option<usize>> {
+            let data: Vec<u8> = (0..200).collect();
+            let profile = FaultProfile { max_chunk: 16, stall_probability: 0.3, error_probability: 0.0 };
+            let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(data)).with_random_faults(seed, profile));
+            let mut cx = Context::from_waker(Waker::noop());
+            let mut buf = [0u8; 32];
+            let mut sizes = Vec::new();
+            loop {
+                match cursor.as_mut().poll_read(&mut cx, &mut buf) {
+                    Poll::Ready(Ok(0)) => return sizes,
+                    Poll::Ready(Ok(n)) => sizes.push(Some(n)),
+                    Poll::Ready(Err(e)) => panic!("{}", e),
+                    Poll::Pending => sizes.push(None),
+                }
+            }
+        }
+
+        let sizes = read_sizes(42);
+        assert_eq!(sizes, read_sizes(42));
+        assert_ne!(sizes, read_sizes(43));
+        assert!(sizes.contains(&None));
+        assert!(sizes.iter().flatten().all(|&n| (1..=16).contains(&n)));
+        assert_eq!(sizes.iter().flatten().sum::<usize>(), 200);
+    }
+
+    #[test]
+    fn random_errors() {
+        let profile = FaultProfile { max_chunk: 0, stall_probability: 0.0, error_probability: 1.0 };
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())).with_random_faults(7, profile));
+        let err = block_on(cursor.as_mut().write(&[1])).unwrap_err();
+        assert_eq!(err.to_string(), "injected random write failure");
+    }
+
+    #[test]
+    fn sticky_eof() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2])).with_sticky_eof());
+        let mut buf = [0u8; 4];
+        block_on(async {
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 2);
+            cursor.as_mut().set_position(0);
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 2);
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 0);
+            cursor.as_mut().seek(SeekFrom::Start(0)).await.unwrap();
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 0);
+            let mut line = String::new();
+            assert_eq!(cursor.as_mut().read_line(&mut line).await.unwrap(), 0);
+            cursor.as_mut().reset_eof();
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn read_uninit() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])).with_read_schedule(vec![2]));
+        let mut buf = [MaybeUninit::<u8>::uninit(); 8];
+        block_on(async {
+            assert_eq!(cursor.as_mut().read_uninit(&mut buf).await.unwrap(), [1, 2]);
+            assert_eq!(cursor.as_mut().read_uninit(&mut buf).await.unwrap(), [3]);
+            assert!(cursor.as_mut().read_uninit(&mut buf).await.unwrap().is_empty());
+        });
+        assert_eq!(cursor.bytes_read(), 3);
+    }
+
+    #[test]
+    fn char_aligned_text() {
+        let text = "añ€😀";
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(String::from(text))).char_aligned().with_read_schedule(vec![2, 2, 4]));
+        let mut buf = [0u8; 3];
+        let mut reads = Vec::new();
+        block_on(async {
+            loop {
+                let n = cursor.as_mut().read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                reads.push(buf[..n].to_vec());
+            }
+        });
+        // The last character is longer than the buffer, so it has to be split.
+        let expected: Vec<&[u8]> = vec![b"a", "ñ".as_bytes(), "€".as_bytes(), &"😀".as_bytes()[..3], &"😀".as_bytes()[3..]];
+        assert_eq!(reads, expected);
+
+        let mut line = String::new();
+        cursor.as_mut().set_position(0);
+        block_on(cursor.as_mut().read_line(&mut line)).unwrap();
+        assert_eq!(line, text);
+
+        assert_eq!(block_on(cursor.as_mut().write(&[0xff])).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(block_on(cursor.as_mut().write("!".as_bytes())).unwrap(), 1);
+        assert_eq!(cursor.get_ref(), "añ€😀!");
+    }
+
+    #[test]
+    fn exact_growth() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())).with_exact_growth());
+        block_on(async {
+            for len in 1..=20 {
+                cursor.as_mut().write(&[len as u8]).await.unwrap();
+                assert_eq!(cursor.get_ref().capacity(), len);
+            }
+            cursor.as_mut().set_position(30);
+            cursor.as_mut().write(&[1, 2]).await.unwrap();
+        });
+        assert_eq!(cursor.get_ref().capacity(), 32);
+    }
+
+    #[test]
+    fn collect_all() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"skip the rest".to_vec())).with_read_schedule(vec![5, 1]));
+        cursor.as_mut().set_position(5);
+        assert_eq!(block_on(cursor.as_mut().collect_all()).unwrap(), b"the rest");
+        assert!(block_on(cursor.as_mut().collect_all()).unwrap().is_empty());
+
+        let mut failing = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 10])).with_read_schedule(vec![4]).fail_read_after(6));
+        assert!(block_on(failing.as_mut().collect_all()).is_err());
+        assert_eq!(failing.position(), 4);
+    }
+
+    #[test]
+    fn write_str() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        assert_eq!(block_on(cursor.as_mut().write_str("naïve ☃")).unwrap(), 10);
+        assert_eq!(cursor.bytes_ref(), "naïve ☃".as_bytes());
+    }
+
+    #[test]
+    fn read_fill() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2])).with_read_fill(0xAA));
+        let mut buf = [0u8; 4];
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 2);
+        assert_eq!(buf, [1, 2, 0xAA, 0xAA]);
+    }
+
+    #[test]
+    fn same_thread_polls() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2])).assert_same_thread());
+        block_on(async {
+            cursor.as_mut().write(&[3]).await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(0)).await.unwrap();
+            cursor.as_mut().read(&mut [0u8; 2]).await.unwrap();
+        });
+    }
+
+    #[test]
+    fn other_thread_poll_panics() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2])).assert_same_thread());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(cursor.as_mut().poll_read(&mut cx, &mut [0u8; 1]).is_ready());
+        let result = std::thread::scope(|s| {
+            s.spawn(|| {
+                let mut cx = Context::from_waker(Waker::noop());
+                let _ = cursor.as_mut().poll_read(&mut cx, &mut [0u8; 1]);
+            }).join()
+        });
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("poll_read polled on thread"), "{}", message);
+        assert!(message.contains(&format!("{:?}", std::thread::current().id())), "{}", message);
+    }
+
+    #[test]
+    fn fixed_array() {
+        assert_not_impl_all!(PinCursor<[u8; 8]>: Unpin);
+        assert_impl_all!(PinCursor<[u8; 8]>: Read, Write, Seek);
+        let mut cursor = Box::pin(PinCursor::from_array([0u8; 8]));
+        block_on(async {
+            assert_eq!(cursor.as_mut().write(&[1, 2, 3, 4, 5, 6]).await.unwrap(), 6);
+            assert_eq!(cursor.as_mut().write(&[7, 8, 9, 10]).await.unwrap(), 2);
+            assert_eq!(cursor.as_mut().write(&[11]).await.unwrap(), 0);
+            cursor.as_mut().set_position(4);
+            let mut buf = [0u8; 8];
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 4);
+            assert_eq!(buf[..4], [5, 6, 7, 8]);
+        });
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn bytes_backing() {
+        use bytes::{Bytes, BytesMut};
+
+        assert_impl_all!(PinCursor<BytesMut>: Read, Write, Seek);
+        assert_impl_all!(PinCursor<Bytes>: Read, BufRead, Seek);
+        assert_not_impl_all!(PinCursor<Bytes>: Write);
+
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(BytesMut::from(&b"head:"[..]))));
+        block_on(async {
+            cursor.as_mut().seek(SeekFrom::End(0)).await.unwrap();
+            cursor.as_mut().write(b"body").await.unwrap();
+            cursor.as_mut().seek(SeekFrom::Start(0)).await.unwrap();
+            cursor.as_mut().write(b"HEAD").await.unwrap();
+        });
+        let mut data = cursor.as_mut().replace_buffer(BytesMut::new());
+        assert_eq!(&data[..], b"HEAD:body");
+        let head = data.split_to(5);
+        assert_eq!(&head[..], b"HEAD:");
+        assert_eq!(&data[..], b"body");
+
+        block_on(cursor.as_mut().write(b"frozen")).unwrap();
+        let frozen = cursor.as_mut().freeze();
+        assert_eq!(cursor.position(), 0);
+        assert!(cursor.is_empty());
+
+        let mut reader = Box::pin(PinCursor::wrap(Cursor::new(frozen)));
+        let mut buf = [0u8; 8];
+        assert_eq!(block_on(reader.as_mut().read(&mut buf)).unwrap(), 6);
+        assert_eq!(&buf[..6], b"frozen");
+    }
+
+    #[test]
+    fn replace_buffer() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        let steps = [(vec![1u8, 2], vec![]), (vec![3u8, 4, 5], vec![1u8, 2])];
+        for (input, previous) in steps {
+            let expected = input.clone();
+            let old = cursor.as_mut().replace_buffer(input);
+            assert_eq!(old, previous);
+            assert_eq!(cursor.position(), 0);
+            let mut buf = Vec::new();
+            block_on(async {
+                let mut chunk = [0u8; 2];
+                loop {
+                    let n = cursor.as_mut().read(&mut chunk).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+            });
+            assert_eq!(buf, expected);
+        }
+    }
+
+    #[test]
+    fn cow_buffer() {
+        let fixture = [1u8, 2, 3];
+        let mut cursor = Box::pin(PinCursor::from(Cow::Borrowed(&fixture[..])));
+        let mut buf = [0u8; 2];
+        assert_eq!(block_on(cursor.as_mut().read(&mut buf)).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+        assert!(!cursor.is_owned());
+
+        assert_eq!(block_on(cursor.as_mut().write(&[4, 5])).unwrap(), 2);
+        assert!(cursor.is_owned());
+        assert_eq!(cursor.bytes_ref(), [1, 2, 4, 5]);
+        assert_eq!(fixture, [1, 2, 3]);
+    }
+
+    #[test]
+    fn cow_promotes_only_on_write() {
+        let fixture = [1u8, 2, 3];
+        let mut cursor = Box::pin(PinCursor::from(Cow::Borrowed(&fixture[..])));
+        assert_eq!(block_on(cursor.as_mut().collect_all()).unwrap(), [1, 2, 3]);
+        block_on(cursor.as_mut().seek(SeekFrom::Start(5))).unwrap();
+        assert!(!cursor.is_owned());
+
+        block_on(cursor.as_mut().write(&[9])).unwrap();
+        assert!(cursor.is_owned());
+        assert_eq!(cursor.bytes_ref(), [1, 2, 3, 0, 0, 9]);
+        assert_eq!(fixture, [1, 2, 3]);
+    }
+
+    #[test]
+    fn wrap_unwrap_keeps_position() {
+        let mut c = Cursor::new(vec![1u8, 2, 3, 4, 5]);
+        c.set_position(3);
+        let cursor = PinCursor::wrap(c);
+        assert_eq!(cursor.position(), 3);
+        let mut c = cursor.unwrap();
+        assert_eq!(c.position(), 3);
+
+        c.set_position(7);
+        let (data, pos, _) = Box::pin(PinCursor::wrap(c)).into_parts();
+        assert_eq!((data.len(), pos), (5, 7));
+    }
+
+    #[test]
+    fn std_cursor_round_trip() {
+        let mut c = std::io::Cursor::new(vec![1u8, 2, 3, 4]);
+        c.set_position(3);
+        let cursor = PinCursor::from(c);
+        assert_eq!(cursor.position(), 3);
+        let c = cursor.into_std_cursor();
+        assert_eq!(c.position(), 3);
+        assert_eq!(c.into_inner(), vec![1, 2, 3, 4]);
     }
 }
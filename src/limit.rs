@@ -0,0 +1,140 @@
+use std::convert::TryFrom;
+use std::io::Result;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{BufRead, Read};
+use pin_project_lite::pin_project;
+
+use crate::PinCursor;
+
+pin_project! {
+    /// A `!Unpin` view of a cursor that reports EOF after a number of bytes.
+    ///
+    /// Created by [`PinCursor::take`]. Reads that would cross the limit are truncated to it.
+    pub struct LimitCursor<T> {
+        inner: Pin<Box<PinCursor<T>>>,
+        limit: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> PinCursor<T>
+    where T: AsRef<[u8]>
+{
+    /// Limits reads from the cursor to `limit` more bytes.
+    pub fn take(self: Pin<Box<Self>>, limit: u64) -> LimitCursor<T> {
+        LimitCursor { inner: self, limit, _p: PhantomPinned }
+    }
+}
+
+impl<T> LimitCursor<T> {
+    /// The number of bytes that can still be read before EOF.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn set_limit(self: Pin<&mut Self>, limit: u64) {
+        *self.project().limit = limit;
+    }
+
+    pub fn get_ref(&self) -> &PinCursor<T> {
+        &self.inner
+    }
+
+    /// Returns the underlying cursor, at the position where reading stopped.
+    pub fn into_inner(self: Pin<Box<Self>>) -> Pin<Box<PinCursor<T>>> {
+        // SAFETY: nothing in a LimitCursor depends on its address, and the cursor it owns
+        // stays pinned in its own box.
+        unsafe { Pin::into_inner_unchecked(self) }.inner
+    }
+
+    fn allowed(&self, len: usize) -> usize {
+        len.min(usize::try_from(self.limit).unwrap_or(usize::MAX))
+    }
+}
+
+impl<T> Read for LimitCursor<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        if self.limit == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let allowed = self.allowed(buf.len());
+        let this = self.project();
+        let n = ready!(this.inner.as_mut().poll_read(cx, &mut buf[..allowed]))?;
+        *this.limit -= n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T> BufRead for LimitCursor<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        if self.limit == 0 {
+            return Poll::Ready(Ok(&[]));
+        }
+        let allowed = self.allowed(usize::MAX);
+        let available = ready!(self.project().inner.as_mut().poll_fill_buf(cx))?;
+        Poll::Ready(Ok(&available[..allowed.min(available.len())]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let amt = self.allowed(amt);
+        let this = self.project();
+        this.inner.as_mut().consume(amt);
+        *this.limit -= amt as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::{Cursor, Seek, Write};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(LimitCursor<Vec<u8>>: Unpin, Write, Seek);
+        assert_impl_all!(LimitCursor<Vec<u8>>: Read, BufRead);
+    }
+
+    #[test]
+    fn eof_at_limit() {
+        let cursor = Box::pin(PinCursor::wrap(Cursor::new(b"0123456789".to_vec())));
+        let mut limited = Box::pin(cursor.take(4));
+        let mut buf = [0u8; 6];
+        block_on(async {
+            assert_eq!(limited.read(&mut buf).await.unwrap(), 4);
+            assert_eq!(&buf[..4], b"0123");
+            assert_eq!(limited.read(&mut buf).await.unwrap(), 0);
+        });
+        assert_eq!(limited.limit(), 0);
+        assert_eq!(limited.get_ref().position(), 4);
+
+        limited.as_mut().set_limit(3);
+        let mut line = String::new();
+        assert_eq!(block_on(limited.read_line(&mut line)).unwrap(), 3);
+        assert_eq!(line, "456");
+        assert_eq!(limited.get_ref().position(), 7);
+    }
+
+    #[test]
+    fn recover_inner() {
+        let cursor = Box::pin(PinCursor::wrap(Cursor::new(b"abcdef".to_vec())));
+        let mut limited = Box::pin(cursor.take(2));
+        let mut out = Vec::new();
+        assert_eq!(block_on(limited.read_to_end(&mut out)).unwrap(), 2);
+        let mut cursor = limited.into_inner();
+        out.clear();
+        assert_eq!(block_on(cursor.read_to_end(&mut out)).unwrap(), 4);
+        assert_eq!(out, b"cdef");
+    }
+}
@@ -0,0 +1,109 @@
+use std::io::{IoSlice, Result};
+use std::marker::PhantomPinned;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::Write;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A `!Unpin` analogue of `/dev/null`: a bottomless sink.
+    ///
+    /// Writes accept the whole buffer, or at most [`with_cap`](NullCursor::with_cap) bytes of it,
+    /// and discard it. Writes of an empty buffer return `Ok(0)`; vectored writes take
+    /// the buffers in order. Flushing and closing always succeed, and closing changes nothing.
+    pub struct NullCursor {
+        cap: Option<NonZeroUsize>,
+        polls: u64,
+        bytes_written: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl NullCursor {
+    pub fn new() -> Self {
+        Self { cap: None, polls: 0, bytes_written: 0, _p: PhantomPinned }
+    }
+
+    /// Makes every write accept at most `cap` bytes. The cap cannot be zero, which would make
+    /// writes of non-empty buffers return `Ok(0)`.
+    pub fn with_cap(mut self, cap: NonZeroUsize) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    /// How many times `poll_write` has been called, including calls with empty buffers.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+}
+
+impl Default for NullCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for NullCursor {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        self.poll_write_vectored(cx, &[IoSlice::new(buf)])
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, _cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<Result<usize>> {
+        let this = self.project();
+        *this.polls += 1;
+        let total = bufs.iter().map(|buf| buf.len()).fold(0usize, usize::saturating_add);
+        let n = total.min(this.cap.map_or(usize::MAX, NonZeroUsize::get));
+        *this.bytes_written += n as u64;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::{Read, Seek, copy};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use crate::ZeroCursor;
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(NullCursor: Unpin, Read, Seek);
+        assert_impl_all!(NullCursor: Write, Send);
+    }
+
+    #[test]
+    fn copy_zeroes_to_null() {
+        const MIB: u64 = 1024 * 1024;
+        let mut zero = Box::pin(ZeroCursor::new());
+        let mut null = Box::pin(NullCursor::new().with_cap(NonZeroUsize::new(1000).unwrap()));
+        let copied = block_on(async {
+            let copied = copy(&mut (&mut zero).take(MIB), &mut null).await.unwrap();
+            null.flush().await.unwrap();
+            std::future::poll_fn(|cx| null.as_mut().poll_close(cx)).await.unwrap();
+            copied
+        });
+        assert_eq!(copied, MIB);
+        assert_eq!(zero.bytes_read(), MIB);
+        assert_eq!(null.bytes_written(), MIB);
+        assert!(null.polls() >= MIB / 1000);
+    }
+}
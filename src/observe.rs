@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+use std::io::{Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker, ready};
+
+use async_std::io::{Seek, Write};
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor};
+
+/// Something that happened to an [`ObservableCursor`], as seen by its subscribers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteEvent {
+    /// A successful write, with the bytes it accepted.
+    Write(Vec<u8>),
+    Flush,
+    Close,
+}
+
+struct Queue {
+    events: VecDeque<WriteEvent>,
+    capacity: Option<usize>,
+    dropped: u64,
+    waker: Option<Waker>,
+    // Set when the cursor is gone and no more events will come.
+    finished: bool,
+}
+
+fn lock(queue: &Mutex<Queue>) -> MutexGuard<'_, Queue> {
+    queue.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// The senders' side of all subscriptions; ends them when dropped together with the cursor.
+#[derive(Default)]
+struct Subscribers {
+    queues: Vec<Arc<Mutex<Queue>>>,
+}
+
+impl Subscribers {
+    fn send(&mut self, event: WriteEvent) {
+        // Subscriptions that have been dropped are only referenced from here.
+        self.queues.retain(|queue| Arc::strong_count(queue) > 1);
+        for queue in &self.queues {
+            let mut queue = lock(queue);
+            if queue.capacity.is_some_and(|capacity| queue.events.len() >= capacity) {
+                queue.dropped += 1;
+            } else {
+                queue.events.push_back(event.clone());
+            }
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Drop for Subscribers {
+    fn drop(&mut self) {
+        for queue in &self.queues {
+            let mut queue = lock(queue);
+            queue.finished = true;
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// A `!Unpin` wrapper that broadcasts every write, flush and close of a cursor
+    /// to any number of subscribers.
+    ///
+    /// Each [`subscribe`](ObservableCursor::subscribe) call returns a [`Subscription`] stream
+    /// of [`WriteEvent`]s, which ends once the cursor is dropped. Subscriptions buffer events
+    /// without limit, unless [`with_subscriber_capacity`](ObservableCursor::with_subscriber_capacity)
+    /// is used, in which case events that do not fit are dropped and counted.
+    pub struct ObservableCursor<T> {
+        inner: Pin<Box<PinCursor<T>>>,
+        subscribers: Subscribers,
+        capacity: Option<usize>,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+/// A stream of the events of an [`ObservableCursor`], created by [`subscribe`](ObservableCursor::subscribe).
+pub struct Subscription {
+    queue: Arc<Mutex<Queue>>,
+}
+
+impl<T> ObservableCursor<T>
+    where T: Buffer
+{
+    pub fn new(inner: PinCursor<T>) -> Self {
+        Self { inner: Box::pin(inner), subscribers: Subscribers::default(), capacity: None, _p: PhantomPinned }
+    }
+
+    /// Makes subscriptions created afterwards hold at most `events` undelivered events.
+    pub fn with_subscriber_capacity(mut self, events: usize) -> Self {
+        self.capacity = Some(events);
+        self
+    }
+
+    /// Starts delivering events to a new subscriber. Earlier events are not replayed.
+    pub fn subscribe(self: Pin<&mut Self>) -> Subscription {
+        let this = self.project();
+        let queue = Arc::new(Mutex::new(Queue {
+            events: VecDeque::new(),
+            capacity: *this.capacity,
+            dropped: 0,
+            waker: None,
+            finished: false,
+        }));
+        this.subscribers.queues.push(queue.clone());
+        Subscription { queue }
+    }
+
+    pub fn get_ref(&self) -> &PinCursor<T> {
+        &self.inner
+    }
+}
+
+impl Subscription {
+    /// How many events did not fit into this subscription's buffer.
+    pub fn dropped(&self) -> u64 {
+        lock(&self.queue).dropped
+    }
+}
+
+impl Stream for Subscription {
+    type Item = WriteEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<WriteEvent>> {
+        let mut queue = lock(&self.queue);
+        match queue.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None if queue.finished => Poll::Ready(None),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Write for ObservableCursor<T>
+    where T: Buffer
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let n = ready!(this.inner.as_mut().poll_write(cx, buf))?;
+        if n > 0 {
+            this.subscribers.send(WriteEvent::Write(buf[..n].to_vec()));
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.project();
+        ready!(this.inner.as_mut().poll_flush(cx))?;
+        this.subscribers.send(WriteEvent::Flush);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.project();
+        ready!(this.inner.as_mut().poll_close(cx))?;
+        this.subscribers.send(WriteEvent::Close);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Seek for ObservableCursor<T>
+    where T: Buffer
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        self.project().inner.as_mut().poll_seek(cx, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+
+    use async_std::io::{Cursor, Read};
+    use async_std::io::prelude::*;
+    use async_std::task::{block_on, spawn};
+    use futures::StreamExt;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(ObservableCursor<Vec<u8>>: Unpin, Read);
+        assert_impl_all!(ObservableCursor<Vec<u8>>: Write, Seek, Send);
+        assert_impl_all!(Subscription: Stream, Send, Unpin);
+    }
+
+    #[test]
+    fn two_subscribers() {
+        let mut cursor = Box::pin(ObservableCursor::new(PinCursor::wrap(Cursor::new(Vec::new()))));
+        let first = cursor.as_mut().subscribe();
+        let second = cursor.as_mut().subscribe();
+        block_on(async {
+            let first = spawn(first.collect::<Vec<_>>());
+            let second = spawn(second.collect::<Vec<_>>());
+            cursor.write_all(b"ab").await.unwrap();
+            cursor.flush().await.unwrap();
+            cursor.write_all(b"c").await.unwrap();
+            poll_fn(|cx| cursor.as_mut().poll_close(cx)).await.unwrap();
+            drop(cursor);
+            let expected = vec![
+                WriteEvent::Write(b"ab".to_vec()),
+                WriteEvent::Flush,
+                WriteEvent::Write(b"c".to_vec()),
+                WriteEvent::Close,
+            ];
+            assert_eq!(first.await, expected);
+            assert_eq!(second.await, expected);
+        });
+    }
+
+    #[test]
+    fn slow_subscriber_drops_events() {
+        let mut cursor = Box::pin(ObservableCursor::new(PinCursor::wrap(Cursor::new(Vec::new()))).with_subscriber_capacity(2));
+        let mut slow = cursor.as_mut().subscribe();
+        block_on(async {
+            for byte in 0..5u8 {
+                cursor.write_all(&[byte]).await.unwrap();
+            }
+        });
+        assert_eq!(slow.dropped(), 3);
+        drop(cursor);
+        let events: Vec<_> = block_on((&mut slow).collect());
+        assert_eq!(events, [WriteEvent::Write(vec![0]), WriteEvent::Write(vec![1])]);
+    }
+}
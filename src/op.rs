@@ -0,0 +1,103 @@
+use std::io::SeekFrom;
+
+/// A completed operation, as recorded by a cursor's op log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    /// `len` bytes were read starting at `pos`.
+    Read { pos: u64, len: usize },
+    /// `len` bytes were written starting at `pos`.
+    Write { pos: u64, len: usize },
+    /// A seek to `target` landed at the absolute position `pos`.
+    Seek { target: SeekFrom, pos: u64 },
+    Flush,
+    Close,
+}
+
+/// The target of the `log` records that describe completed operations.
+#[cfg(feature = "log")]
+pub(crate) const LOG_TARGET: &str = "pin_cursor";
+
+/// Records successfully completed operations, if enabled.
+///
+/// With the `log` feature, it also traces every operation, enabled or not.
+#[derive(Default)]
+pub(crate) struct OpLog {
+    enabled: bool,
+    ops: Vec<Op>,
+}
+
+impl OpLog {
+    pub(crate) fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub(crate) fn record(&mut self, op: Op) {
+        #[cfg(feature = "log")]
+        match &op {
+            Op::Read { pos, len } => log::trace!(target: LOG_TARGET, "read {} bytes at {}", len, pos),
+            Op::Write { pos, len } => log::trace!(target: LOG_TARGET, "wrote {} bytes at {}", len, pos),
+            Op::Seek { target, pos } => log::trace!(target: LOG_TARGET, "seek to {:?} landed at {}", target, pos),
+            Op::Flush => log::trace!(target: LOG_TARGET, "flush"),
+            Op::Close => log::trace!(target: LOG_TARGET, "close"),
+        }
+        if self.enabled {
+            self.ops.push(op);
+        }
+    }
+
+    pub(crate) fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_std::io::Cursor;
+    use async_std::task::block_on;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    use crate::PinCursor;
+
+    use super::*;
+
+    static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct Capture;
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == LOG_TARGET
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if self.enabled(record.metadata()) && record.level() == Level::Trace {
+                RECORDS.lock().unwrap_or_else(|e| e.into_inner()).push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn traces_operations() {
+        log::set_logger(&Capture).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+
+        // The op log is not enabled, but everything is traced all the same.
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![0u8; 4242])));
+        block_on(async {
+            assert_eq!(cursor.as_mut().seek(SeekFrom::End(0)).await.unwrap(), 4242);
+            assert_eq!(cursor.as_mut().write(&[1; 17]).await.unwrap(), 17);
+            assert_eq!(cursor.as_mut().seek(SeekFrom::End(-1)).await.unwrap(), 4258);
+            assert_eq!(cursor.as_mut().read(&mut [0; 8]).await.unwrap(), 1);
+        });
+        assert!(cursor.op_log().is_empty());
+
+        let records = RECORDS.lock().unwrap_or_else(|e| e.into_inner());
+        assert!(records.iter().any(|r| r == "wrote 17 bytes at 4242"), "{:?}", records);
+        assert!(records.iter().any(|r| r == "seek to End(-1) landed at 4258"), "{:?}", records);
+        assert!(records.iter().any(|r| r == "read 1 bytes at 4258"), "{:?}", records);
+    }
+}
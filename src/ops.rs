@@ -0,0 +1,97 @@
+//! Operations shared by the high-level methods of the wrappers in this crate.
+
+use std::io::{Error, ErrorKind, Result};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{BufRead, BufReadExt, Read, ReadExt, Write, WriteExt};
+
+pub(crate) async fn read_exact<R>(mut r: Pin<&mut R>, buf: &mut [u8]) -> Result<()>
+    where R: Read + ?Sized
+{
+    r.read_exact(buf).await
+}
+
+pub(crate) async fn read_to_end<R>(mut r: Pin<&mut R>, buf: &mut Vec<u8>) -> Result<usize>
+    where R: Read + ?Sized
+{
+    r.read_to_end(buf).await
+}
+
+// Reads everything up to EOF into `buf`, then hands it over. `buf` is kept zero-filled past
+// `filled`, so each byte is initialized once and the buffer only grows by doubling.
+pub(crate) fn poll_collect_all<R>(mut r: Pin<&mut R>, cx: &mut Context<'_>, buf: &mut Vec<u8>, filled: &mut usize) -> Poll<Result<Vec<u8>>>
+    where R: Read + ?Sized
+{
+    loop {
+        if *filled == buf.len() {
+            buf.resize((buf.len() * 2).max(32), 0);
+        }
+        match ready!(r.as_mut().poll_read(cx, &mut buf[*filled..]))? {
+            0 => {
+                buf.truncate(std::mem::replace(filled, 0));
+                return Poll::Ready(Ok(std::mem::take(buf)));
+            }
+            n => *filled += n,
+        }
+    }
+}
+
+pub(crate) async fn write_all<W>(mut w: Pin<&mut W>, buf: &[u8]) -> Result<()>
+    where W: Write + ?Sized
+{
+    w.write_all(buf).await
+}
+
+// Appends to `buf` up to and including `delim`, or up to EOF. `read` accumulates across polls.
+// Backs the nameable futures, which cannot hold the anonymous ones of `BufReadExt`.
+pub(crate) fn poll_read_until<R>(mut r: Pin<&mut R>, cx: &mut Context<'_>, delim: u8, buf: &mut Vec<u8>, read: &mut usize) -> Poll<Result<usize>>
+    where R: BufRead + ?Sized
+{
+    loop {
+        let (done, used) = {
+            let available = ready!(r.as_mut().poll_fill_buf(cx))?;
+            match available.iter().position(|&b| b == delim) {
+                Some(i) => {
+                    buf.extend_from_slice(&available[..=i]);
+                    (true, i + 1)
+                }
+                None => {
+                    buf.extend_from_slice(available);
+                    (available.is_empty(), available.len())
+                }
+            }
+        };
+        r.as_mut().consume(used);
+        *read += used;
+        if done {
+            return Poll::Ready(Ok(std::mem::replace(read, 0)));
+        }
+    }
+}
+
+pub(crate) async fn read_until<R>(mut r: Pin<&mut R>, delim: u8, buf: &mut Vec<u8>) -> Result<usize>
+    where R: BufRead + ?Sized
+{
+    r.read_until(delim, buf).await
+}
+
+// Collects a line in `bytes` and appends it to `buf` only once it is known to be valid UTF-8.
+pub(crate) fn poll_read_line<R>(r: Pin<&mut R>, cx: &mut Context<'_>, buf: &mut String, bytes: &mut Vec<u8>, read: &mut usize) -> Poll<Result<usize>>
+    where R: BufRead + ?Sized
+{
+    let n = ready!(poll_read_until(r, cx, b'\n', bytes, read))?;
+    match std::str::from_utf8(bytes) {
+        Ok(line) => {
+            buf.push_str(line);
+            Poll::Ready(Ok(n))
+        }
+        Err(_) => Poll::Ready(Err(Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8"))),
+    }
+}
+
+pub(crate) async fn read_line<R>(mut r: Pin<&mut R>, buf: &mut String) -> Result<usize>
+    where R: BufRead + ?Sized
+{
+    r.read_line(buf).await
+}
@@ -0,0 +1,262 @@
+//! The shared in-memory byte channel behind the duplex and pipe types.
+
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use async_std::io::{Read, Write};
+use pin_project_lite::pin_project;
+
+struct Pipe {
+    buf: VecDeque<u8>,
+    capacity: Option<usize>,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+    write_closed: bool,
+    read_closed: bool,
+}
+
+fn wake(waker: &mut Option<Waker>) {
+    if let Some(waker) = waker.take() {
+        waker.wake();
+    }
+}
+
+fn lock(pipe: &Mutex<Pipe>) -> MutexGuard<'_, Pipe> {
+    // A panic while holding the lock cannot leave the pipe in an inconsistent state.
+    pipe.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Creates a unidirectional pipe, bounded if `capacity` is given.
+pub(crate) fn pipe(capacity: Option<usize>) -> (PipeWriter, PipeReader) {
+    let pipe = Arc::new(Mutex::new(Pipe {
+        buf: VecDeque::new(),
+        capacity,
+        read_waker: None,
+        write_waker: None,
+        write_closed: false,
+        read_closed: false,
+    }));
+    (PipeWriter(pipe.clone()), PipeReader(pipe))
+}
+
+/// The read end of a pipe. Dropping it makes further writes fail with `BrokenPipe`.
+pub(crate) struct PipeReader(Arc<Mutex<Pipe>>);
+
+impl PipeReader {
+    /// Reads buffered bytes; returns `Pending` if there are none and the writer is still open.
+    pub(crate) fn poll_read(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let mut pipe = lock(&self.0);
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if pipe.buf.is_empty() {
+            if pipe.write_closed {
+                return Poll::Ready(Ok(0));
+            }
+            pipe.read_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(pipe.buf.len());
+        for (dst, src) in buf.iter_mut().zip(pipe.buf.drain(..n)) {
+            *dst = src;
+        }
+        wake(&mut pipe.write_waker);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        let mut pipe = lock(&self.0);
+        pipe.read_closed = true;
+        wake(&mut pipe.write_waker);
+    }
+}
+
+/// The write end of a pipe. Dropping or closing it lets the reader drain and then see EOF.
+pub(crate) struct PipeWriter(Arc<Mutex<Pipe>>);
+
+impl PipeWriter {
+    /// Appends as many bytes as fit; returns `Pending` if the pipe is full.
+    pub(crate) fn poll_write(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let mut pipe = lock(&self.0);
+        if pipe.read_closed {
+            return Poll::Ready(Err(Error::new(ErrorKind::BrokenPipe, "the reading end of the pipe is gone")));
+        }
+        if pipe.write_closed {
+            return Poll::Ready(Err(Error::new(ErrorKind::BrokenPipe, "write after close")));
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let space = match pipe.capacity {
+            Some(capacity) => capacity.saturating_sub(pipe.buf.len()),
+            None => buf.len(),
+        };
+        if space == 0 {
+            pipe.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(space);
+        pipe.buf.extend(&buf[..n]);
+        wake(&mut pipe.read_waker);
+        Poll::Ready(Ok(n))
+    }
+
+    pub(crate) fn close(&self) {
+        let mut pipe = lock(&self.0);
+        pipe.write_closed = true;
+        wake(&mut pipe.read_waker);
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+pin_project! {
+    /// The write end of a [`pin_pipe`].
+    pub struct PinPipeWriter {
+        writer: PipeWriter,
+        polls: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+pin_project! {
+    /// The read end of a [`pin_pipe`].
+    pub struct PinPipeReader {
+        reader: PipeReader,
+        polls: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+/// Creates a `!Unpin` unidirectional pipe that holds at most `capacity` bytes.
+///
+/// Writes to a full pipe return `Poll::Pending` until the reader drains some bytes,
+/// and reads from an empty pipe return `Poll::Pending` until the writer adds some.
+/// Once the writer is closed or dropped, the reader drains what is left and then sees EOF.
+/// Once the reader is dropped, writes fail with `ErrorKind::BrokenPipe`.
+pub fn pin_pipe(capacity: usize) -> (PinPipeWriter, PinPipeReader) {
+    let (writer, reader) = pipe(Some(capacity));
+    (
+        PinPipeWriter { writer, polls: 0, _p: PhantomPinned },
+        PinPipeReader { reader, polls: 0, _p: PhantomPinned },
+    )
+}
+
+impl PinPipeWriter {
+    /// How many times `poll_write` has been called, including the calls that returned `Pending`.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+}
+
+impl PinPipeReader {
+    /// How many times `poll_read` has been called, including the calls that returned `Pending`.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+}
+
+impl Write for PinPipeWriter {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        *this.polls += 1;
+        this.writer.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().writer.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Read for PinPipeReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        *this.polls += 1;
+        this.reader.poll_read(cx, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::prelude::*;
+    use async_std::task::{block_on, spawn, yield_now};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(PinPipeWriter: Unpin, Read);
+        assert_not_impl_any!(PinPipeReader: Unpin, Write);
+        assert_impl_all!(PinPipeWriter: Write, Send);
+        assert_impl_all!(PinPipeReader: Read, Send);
+    }
+
+    #[test]
+    fn backpressure() {
+        let (writer, reader) = pin_pipe(4);
+        let (mut writer, mut reader) = (Box::pin(writer), Box::pin(reader));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 2];
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        assert!(matches!(writer.as_mut().poll_write(&mut cx, &[1, 2, 3, 4, 5, 6]), Poll::Ready(Ok(4))));
+        assert!(writer.as_mut().poll_write(&mut cx, &[5, 6]).is_pending());
+        assert!(matches!(reader.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(2))));
+        assert!(matches!(writer.as_mut().poll_write(&mut cx, &[5, 6, 7]), Poll::Ready(Ok(2))));
+        drop(writer);
+        let mut rest = [0u8; 8];
+        assert!(matches!(reader.as_mut().poll_read(&mut cx, &mut rest), Poll::Ready(Ok(4))));
+        assert_eq!(rest[..4], [3, 4, 5, 6]);
+        assert!(matches!(reader.as_mut().poll_read(&mut cx, &mut rest), Poll::Ready(Ok(0))));
+    }
+
+    #[test]
+    fn broken_pipe() {
+        let (writer, reader) = pin_pipe(4);
+        let mut writer = Box::pin(writer);
+        drop(reader);
+        let err = block_on(writer.write(&[1])).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn slow_reader_throttles_writer() {
+        let (writer, reader) = pin_pipe(4);
+        let (mut writer, mut reader) = (Box::pin(writer), Box::pin(reader));
+        let data: Vec<u8> = (0..32).collect();
+        let expected = data.clone();
+        block_on(async {
+            let writer_task = spawn(async move {
+                writer.write_all(&data).await.unwrap();
+                writer.polls()
+            });
+            let mut received = Vec::new();
+            let mut byte = [0u8; 1];
+            while reader.read(&mut byte).await.unwrap() == 1 {
+                received.push(byte[0]);
+                yield_now().await;
+            }
+            assert_eq!(received, expected);
+            // Without backpressure the whole buffer would have gone through in a single poll;
+            // with a capacity of 4, it takes at least 8.
+            assert!(writer_task.await >= 8);
+        });
+    }
+}
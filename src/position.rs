@@ -0,0 +1,62 @@
+use std::io::{Error, ErrorKind, Result};
+
+/// How a cursor treats positions beyond the end of its buffer; see
+/// [`position_policy`](crate::PinCursor::position_policy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionPolicy {
+    /// Positions past the end are kept, like in `std::io::Cursor`: reads there return EOF,
+    /// and writes zero-fill the gap or are short, depending on the buffer.
+    #[default]
+    Lenient,
+    /// Positions past the end are moved back to the end.
+    Clamp,
+    /// Positions past the end are rejected: seeks fail with `ErrorKind::InvalidInput`,
+    /// and `set_position` panics.
+    Strict,
+}
+
+impl PositionPolicy {
+    pub(crate) fn apply(self, pos: u64, len: u64) -> Result<u64> {
+        match self {
+            PositionPolicy::Lenient => Ok(pos),
+            PositionPolicy::Clamp => Ok(pos.min(len)),
+            PositionPolicy::Strict if pos > len => Err(out_of_range(pos, len)),
+            PositionPolicy::Strict => Ok(pos),
+        }
+    }
+}
+
+pub(crate) fn out_of_range(pos: u64, len: u64) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("position {} is past the end of the buffer of length {}", pos, len),
+    )
+}
+
+/// What a write does when it starts past the end of the buffer; see
+/// [`gap_policy`](crate::PinCursor::gap_policy).
+///
+/// Seeking past the end is always allowed; the policy only applies once something is written there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// Growable buffers fill the gap with zeroes, like `std::io::Cursor`.
+    #[default]
+    ZeroFill,
+    /// The write fails with `ErrorKind::InvalidInput`, naming the size of the gap.
+    Error,
+    /// The gap is left unallocated, which is what a [`SparseCursor`](crate::SparseCursor) does
+    /// by default. A `PinCursor`, whose buffers are contiguous, treats this like `ZeroFill`.
+    Sparse,
+}
+
+impl GapPolicy {
+    pub(crate) fn check(self, pos: u64, len: u64) -> Result<()> {
+        if self == GapPolicy::Error && pos > len {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("write at {} would leave a gap of {} bytes after the end", pos, pos - len),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+/// Probabilities for [`PinCursor::with_random_faults`](crate::PinCursor::with_random_faults).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaultProfile {
+    /// Every operation transfers at most this many bytes, picked uniformly from `1..=max_chunk`.
+    /// Zero leaves chunk sizes alone.
+    pub max_chunk: usize,
+    /// The chance of each poll returning `Poll::Pending` (waking immediately).
+    pub stall_probability: f64,
+    /// The chance of each poll failing with an injected error.
+    pub error_probability: f64,
+}
+
+impl Default for FaultProfile {
+    /// Small chunks and frequent stalls, but no errors.
+    fn default() -> Self {
+        Self { max_chunk: 8, stall_probability: 0.25, error_probability: 0.0 }
+    }
+}
+
+/// A tiny xorshift64* generator, so that faults are reproducible without pulling in `rand`.
+#[derive(Clone, Debug)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // One round of splitmix64, so that similar seeds give unrelated sequences and the state is never 0.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self { state: (z ^ (z >> 31)) | 1 }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `1..=max`.
+    pub(crate) fn up_to(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize + 1
+    }
+
+    pub(crate) fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
@@ -0,0 +1,134 @@
+use std::future::{Future, poll_fn};
+use std::io::{IoSliceMut, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{BufRead, Read};
+use pin_project_lite::pin_project;
+
+use crate::ops;
+
+pin_project! {
+    /// A `!Unpin` wrapper around any `Unpin` reader.
+    ///
+    /// This is the [`PinCursor`](crate::PinCursor) treatment for readers that are not cursors,
+    /// such as `async_std::io::repeat` or a chain of readers.
+    pub struct PinReader<R> {
+        r: R,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<R> PinReader<R>
+    where R: Read + Unpin
+{
+    pub fn wrap(r: R) -> Self {
+        Self { r, _p: PhantomPinned }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.r
+    }
+
+    pub fn get_mut(self: Pin<&mut Self>) -> &mut R {
+        self.project().r
+    }
+
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
+    pub fn read<'a, 'b>(mut self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> impl Future<Output=Result<usize>> + use<'a, 'b, R> {
+        poll_fn(move |cx| self.as_mut().poll_read(cx, buf))
+    }
+
+    pub fn read_exact<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> impl Future<Output=Result<()>> + use<'a, 'b, R> {
+        ops::read_exact(self, buf)
+    }
+
+    pub fn read_to_end<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut Vec<u8>) -> impl Future<Output=Result<usize>> + use<'a, 'b, R> {
+        ops::read_to_end(self, buf)
+    }
+}
+
+impl<R> Read for PinReader<R>
+    where R: Read + Unpin
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        Pin::new(self.project().r).poll_read(cx, buf)
+    }
+
+    fn poll_read_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
+        Pin::new(self.project().r).poll_read_vectored(cx, bufs)
+    }
+}
+
+impl<R> PinReader<R>
+    where R: BufRead + Unpin
+{
+    /// Reads up to and including `delim`, or up to EOF, and appends the bytes to `buf`.
+    ///
+    /// Returns the number of bytes read, which is 0 at EOF.
+    pub fn read_until<'a, 'b>(self: Pin<&'a mut Self>, delim: u8, buf: &'b mut Vec<u8>) -> impl Future<Output=Result<usize>> + use<'a, 'b, R> {
+        ops::read_until(self, delim, buf)
+    }
+
+    /// Reads a line, including the trailing `\n` if there is one, and appends it to `buf`.
+    ///
+    /// Fails with `ErrorKind::InvalidData` if the line is not valid UTF-8.
+    pub fn read_line<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b mut String) -> impl Future<Output=Result<usize>> + use<'a, 'b, R> {
+        ops::read_line(self, buf)
+    }
+}
+
+impl<R> BufRead for PinReader<R>
+    where R: BufRead + Unpin
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        Pin::new(self.project().r).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        Pin::new(self.project().r).consume(amt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::{Cursor, Repeat, repeat};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_all!(PinReader<Repeat>: Unpin);
+        assert_impl_all!(PinReader<Repeat>: Read);
+        assert_impl_all!(PinReader<Cursor<Vec<u8>>>: Read, BufRead);
+    }
+
+    #[test]
+    fn repeat_reader() {
+        let mut reader = Box::pin(PinReader::wrap(repeat(7)));
+        let mut buf = [0u8; 5];
+        block_on(reader.as_mut().read_exact(&mut buf)).unwrap();
+        assert_eq!(buf, [7; 5]);
+        assert_eq!(block_on(reader.as_mut().read(&mut buf[..2])).unwrap(), 2);
+    }
+
+    #[test]
+    fn chained_cursors() {
+        let chain = Cursor::new(vec![1u8, 2]).chain(Cursor::new(vec![3u8]));
+        let mut reader = Box::pin(PinReader::wrap(chain));
+        let mut out = Vec::new();
+        assert_eq!(block_on(reader.as_mut().read_to_end(&mut out)).unwrap(), 3);
+        assert_eq!(out, [1, 2, 3]);
+        let (first, second) = reader.get_ref().get_ref();
+        assert_eq!(first.position(), 2);
+        assert_eq!(second.position(), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{Read, Seek};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A `!Unpin` reader that yields a byte pattern over and over, and never ends.
+    ///
+    /// The cursor has an absolute position like any other, and the byte at position `p`
+    /// is `pattern[p % pattern.len()]`. Seeking relative to the end is not possible,
+    /// since there is none.
+    pub struct RepeatCursor {
+        pattern: Vec<u8>,
+        pos: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl RepeatCursor {
+    /// Fails with `ErrorKind::InvalidInput` if `pattern` is empty.
+    pub fn new(pattern: Vec<u8>) -> Result<Self> {
+        if pattern.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "RepeatCursor needs a non-empty pattern"));
+        }
+        Ok(Self { pattern, pos: 0, _p: PhantomPinned })
+    }
+
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// The index in the pattern of the next byte to be read.
+    pub fn phase(&self) -> usize {
+        (self.pos % self.pattern.len() as u64) as usize
+    }
+}
+
+impl Read for RepeatCursor {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let phase = self.phase();
+        let this = self.project();
+        for (dst, src) in buf.iter_mut().zip(this.pattern.iter().cycle().skip(phase)) {
+            *dst = *src;
+        }
+        *this.pos = this.pos.wrapping_add(buf.len() as u64);
+        Poll::Ready(Ok(buf.len()))
+    }
+}
+
+impl Seek for RepeatCursor {
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.project();
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::Current(n) => this.pos.checked_add_signed(n),
+            SeekFrom::End(_) => {
+                return Poll::Ready(Err(Error::new(ErrorKind::InvalidInput, "RepeatCursor has no end to seek from")));
+            }
+        };
+        match target {
+            Some(n) => {
+                *this.pos = n;
+                Poll::Ready(Ok(n))
+            }
+            None => Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::Write;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    const DEADBEEF: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(RepeatCursor: Unpin, Write);
+        assert_impl_all!(RepeatCursor: Read, Seek, Send);
+    }
+
+    #[test]
+    fn empty_pattern() {
+        assert_eq!(RepeatCursor::new(Vec::new()).err().unwrap().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn odd_reads_across_boundaries() {
+        let mut cursor = Box::pin(RepeatCursor::new(DEADBEEF.to_vec()).unwrap());
+        let mut out = Vec::new();
+        block_on(async {
+            for size in [3, 5, 7, 1, 11] {
+                let mut buf = vec![0u8; size];
+                assert_eq!(cursor.read(&mut buf).await.unwrap(), size);
+                out.extend(buf);
+            }
+        });
+        let expected: Vec<u8> = DEADBEEF.iter().copied().cycle().take(27).collect();
+        assert_eq!(out, expected);
+        assert_eq!(cursor.position(), 27);
+        assert_eq!(cursor.phase(), 3);
+    }
+
+    #[test]
+    fn seek_sets_phase() {
+        let mut cursor = Box::pin(RepeatCursor::new(DEADBEEF.to_vec()).unwrap());
+        let mut buf = [0u8; 2];
+        block_on(async {
+            assert_eq!(cursor.seek(SeekFrom::Start(1001)).await.unwrap(), 1001);
+            assert_eq!(cursor.phase(), 1);
+            cursor.read_exact(&mut buf).await.unwrap();
+            assert_eq!(buf, [0xad, 0xbe]);
+            assert_eq!(cursor.seek(SeekFrom::Current(-3)).await.unwrap(), 1000);
+            cursor.read_exact(&mut buf).await.unwrap();
+            assert_eq!(buf, [0xde, 0xad]);
+            assert!(cursor.seek(SeekFrom::End(0)).await.is_err());
+        });
+    }
+}
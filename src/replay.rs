@@ -0,0 +1,52 @@
+//! The text format of IO traces accepted by [`PinCursor::with_replay`](crate::PinCursor::with_replay).
+
+use std::io::{Error, ErrorKind, Result};
+
+/// The schedules and failures of one direction of a trace.
+#[derive(Default)]
+pub(crate) struct Track {
+    pub(crate) sizes: Vec<usize>,
+    pub(crate) fail_after: Option<u64>,
+}
+
+impl Track {
+    fn push(&mut self, line: usize, arg: &str) -> Result<()> {
+        if self.fail_after.is_some() {
+            return Err(invalid(line, "nothing can follow an error in the same direction"));
+        }
+        if arg == "error" {
+            self.fail_after = Some(self.sizes.iter().map(|&n| n as u64).sum());
+        } else {
+            let size = arg.parse().map_err(|_| invalid(line, "expected a size or `error`"))?;
+            self.sizes.push(size);
+        }
+        Ok(())
+    }
+}
+
+/// Parses a trace: one operation per line, either `read <size>`, `write <size>`,
+/// `read error` or `write error`. Blank lines and lines starting with `#` are ignored.
+pub(crate) fn parse(trace: &str) -> Result<(Track, Track)> {
+    let (mut reads, mut writes) = (Track::default(), Track::default());
+    for (i, line) in trace.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let track = match words.next() {
+            Some("read") => &mut reads,
+            Some("write") => &mut writes,
+            _ => return Err(invalid(i + 1, "expected `read` or `write`")),
+        };
+        match (words.next(), words.next()) {
+            (Some(arg), None) => track.push(i + 1, arg)?,
+            _ => return Err(invalid(i + 1, "expected exactly one argument")),
+        }
+    }
+    Ok((reads, writes))
+}
+
+fn invalid(line: usize, msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("trace line {}: {}", line, msg))
+}
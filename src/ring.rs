@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use async_std::io::{Read, Write};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A `!Unpin` FIFO byte queue with stream semantics.
+    ///
+    /// Writes append to the tail and reads remove from the head, so every written byte
+    /// is read exactly once. Reading from an empty ring returns `Poll::Pending` (the waker is
+    /// woken by the next write) until [`close_writes`](RingCursor::close_writes) is called;
+    /// after that, reads drain what is left and then return `Ok(0)`, and writes fail with
+    /// `ErrorKind::BrokenPipe`. There is no `Seek`.
+    pub struct RingCursor {
+        buf: VecDeque<u8>,
+        closed: bool,
+        read_waker: Option<Waker>,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl RingCursor {
+    pub fn new() -> Self {
+        Self::with_data(Vec::new())
+    }
+
+    /// Creates a ring that already holds `data`, ready to be read.
+    pub fn with_data(data: impl Into<VecDeque<u8>>) -> Self {
+        Self { buf: data.into(), closed: false, read_waker: None, _p: PhantomPinned }
+    }
+
+    /// The number of bytes written but not read yet.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Marks the end of the stream: once the ring is drained, reads return `Ok(0)`.
+    pub fn close_writes(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.closed = true;
+        if let Some(waker) = this.read_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for RingCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for RingCursor {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if this.buf.is_empty() {
+            if *this.closed {
+                return Poll::Ready(Ok(0));
+            }
+            *this.read_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(this.buf.len());
+        for (dst, src) in buf.iter_mut().zip(this.buf.drain(..n)) {
+            *dst = src;
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Write for RingCursor {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if *this.closed {
+            return Poll::Ready(Err(Error::new(ErrorKind::BrokenPipe, "write after close_writes")));
+        }
+        this.buf.extend(buf);
+        if let Some(waker) = this.read_waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Same as [`close_writes`](RingCursor::close_writes).
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.close_writes();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::Seek;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(RingCursor: Unpin, Seek);
+        assert_impl_all!(RingCursor: Read, Write);
+    }
+
+    #[test]
+    fn fifo_exactly_once() {
+        let mut ring = Box::pin(RingCursor::new());
+        let mut buf = [0u8; 3];
+        block_on(async {
+            ring.write_all(&[1, 2, 3, 4]).await.unwrap();
+            assert_eq!(ring.read(&mut buf).await.unwrap(), 3);
+            assert_eq!(buf, [1, 2, 3]);
+            ring.write_all(&[5, 6]).await.unwrap();
+            assert_eq!(ring.len(), 3);
+            assert_eq!(ring.read(&mut buf).await.unwrap(), 3);
+            assert_eq!(buf, [4, 5, 6]);
+        });
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn pending_until_closed() {
+        let mut ring = Box::pin(RingCursor::with_data(vec![1u8]));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 2];
+        assert!(matches!(ring.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(1))));
+        assert!(ring.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        assert!(matches!(ring.as_mut().poll_write(&mut cx, &[2]), Poll::Ready(Ok(1))));
+        ring.as_mut().close_writes();
+        assert!(matches!(ring.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(1))));
+        assert_eq!(buf[0], 2);
+        assert!(matches!(ring.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(0))));
+        assert!(matches!(ring.as_mut().poll_write(&mut cx, &[3]), Poll::Ready(Err(_))));
+    }
+}
@@ -0,0 +1,320 @@
+use std::convert::TryFrom;
+use std::future::{Future, poll_fn};
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::task::{Context, Poll, Waker, ready};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor};
+
+struct State {
+    data: Vec<u8>,
+    len_wakers: Vec<Waker>,
+}
+
+type Shared = Arc<Mutex<State>>;
+
+fn new_shared(data: Vec<u8>) -> Shared {
+    Arc::new(Mutex::new(State { data, len_wakers: Vec::new() }))
+}
+
+fn lock(shared: &Mutex<State>) -> MutexGuard<'_, State> {
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Never blocks the executor: if the observer holds the lock, ask to be polled again.
+fn poll_lock<'a>(shared: &'a Mutex<State>, cx: &mut Context<'_>) -> Poll<MutexGuard<'a, State>> {
+    match shared.try_lock() {
+        Ok(guard) => Poll::Ready(guard),
+        Err(TryLockError::Poisoned(e)) => Poll::Ready(e.into_inner()),
+        Err(TryLockError::WouldBlock) => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+pin_project! {
+    /// A `!Unpin` cursor over a `Vec<u8>` that an [`Observer`] can look at while it is in use.
+    ///
+    /// Created by [`PinCursor::shared`]. It behaves like a `PinCursor<Vec<u8>>` without
+    /// fault injection. Polls never block on the shared lock: if it is contended, they return
+    /// `Poll::Pending` and wake themselves up to be retried.
+    pub struct PinSharedCursor {
+        shared: Shared,
+        pos: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+/// A read-only view of the buffer behind a [`PinSharedCursor`].
+///
+/// Observers can be cloned and sent to other tasks or threads.
+#[derive(Clone)]
+pub struct Observer {
+    shared: Shared,
+}
+
+/// Where a [`MultiCursorSet`] starts: `MultiCursor::new(data)` creates one over `data`.
+pub type MultiCursor = MultiCursorSet;
+
+/// A buffer that any number of [`PinSharedCursor`]s can be forked from.
+///
+/// Each fork has its own position, starting at 0, and sees the writes of all the others,
+/// like several handles to one file.
+pub struct MultiCursorSet {
+    shared: Shared,
+}
+
+impl MultiCursorSet {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { shared: new_shared(data) }
+    }
+
+    /// Creates a new cursor over the buffer, positioned at its start.
+    pub fn fork(&self) -> PinSharedCursor {
+        PinSharedCursor { shared: self.shared.clone(), pos: 0, _p: PhantomPinned }
+    }
+
+    pub fn observer(&self) -> Observer {
+        Observer { shared: self.shared.clone() }
+    }
+
+    /// Returns the buffer, or gives the set back if some forks or observers still exist.
+    pub fn into_inner(self) -> std::result::Result<Vec<u8>, Self> {
+        match Arc::try_unwrap(self.shared) {
+            Ok(state) => Ok(state.into_inner().unwrap_or_else(|e| e.into_inner()).data),
+            Err(shared) => Err(Self { shared }),
+        }
+    }
+}
+
+impl PinCursor<Vec<u8>> {
+    /// Creates a cursor over `data` together with an observer of that same buffer.
+    pub fn shared(data: Vec<u8>) -> (PinSharedCursor, Observer) {
+        let shared = new_shared(data);
+        let cursor = PinSharedCursor { shared: shared.clone(), pos: 0, _p: PhantomPinned };
+        (cursor, Observer { shared })
+    }
+}
+
+impl PinSharedCursor {
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    pub fn set_position(self: Pin<&mut Self>, pos: u64) {
+        *self.project().pos = pos;
+    }
+
+    pub fn read<'a, 'b>(mut self: Pin<&'a mut Self>, buf: &'b mut [u8]) -> impl Future<Output=Result<usize>> + use<'a, 'b> {
+        poll_fn(move |cx| self.as_mut().poll_read(cx, buf))
+    }
+
+    pub fn write<'a, 'b>(mut self: Pin<&'a mut Self>, buf: &'b [u8]) -> impl Future<Output=Result<usize>> + use<'a, 'b> {
+        poll_fn(move |cx| self.as_mut().poll_write(cx, buf))
+    }
+
+    pub fn seek(mut self: Pin<&mut Self>, pos: SeekFrom) -> impl Future<Output=Result<u64>> + '_ {
+        poll_fn(move |cx| self.as_mut().poll_seek(cx, pos))
+    }
+}
+
+impl Read for PinSharedCursor {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let state = ready!(poll_lock(this.shared, cx));
+        let data = state.data.as_slice();
+        let start = usize::try_from(*this.pos).unwrap_or(usize::MAX).min(data.len());
+        let n = (data.len() - start).min(buf.len());
+        buf[..n].copy_from_slice(&data[start..start + n]);
+        *this.pos += n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Write for PinSharedCursor {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let mut state = ready!(poll_lock(this.shared, cx));
+        let n = state.data.write_at(*this.pos, buf)?;
+        *this.pos += n as u64;
+        for waker in state.len_wakers.drain(..) {
+            waker.wake();
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Seek for PinSharedCursor {
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.project();
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                *this.pos = n;
+                return Poll::Ready(Ok(n));
+            }
+            SeekFrom::End(n) => (ready!(poll_lock(this.shared, cx)).data.len() as u64, n),
+            SeekFrom::Current(n) => (*this.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                *this.pos = n;
+                Poll::Ready(Ok(n))
+            }
+            None => Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ))),
+        }
+    }
+}
+
+impl Observer {
+    /// A copy of everything in the buffer right now.
+    pub fn snapshot(&self) -> Vec<u8> {
+        lock(&self.shared).data.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.shared).data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolves once the buffer holds at least `n` bytes.
+    pub fn wait_for_len(&self, n: usize) -> impl Future<Output=()> + '_ {
+        poll_fn(move |cx| {
+            let mut state = lock(&self.shared);
+            if state.data.len() >= n {
+                Poll::Ready(())
+            } else {
+                // Re-polls of the same task must not pile up wakers until the next write.
+                if !state.len_wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    state.len_wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(PinSharedCursor: Unpin);
+        assert_impl_all!(PinSharedCursor: Read, Write, Seek, Send);
+        assert_impl_all!(Observer: Clone, Send, Sync);
+        assert_impl_all!(MultiCursorSet: Send, Sync);
+    }
+
+    #[test]
+    fn round_trip() {
+        let (cursor, observer) = PinCursor::shared(vec![1, 2]);
+        let mut cursor = Box::pin(cursor);
+        let mut buf = [0u8; 4];
+        block_on(async {
+            assert_eq!(cursor.as_mut().seek(SeekFrom::End(0)).await.unwrap(), 2);
+            assert_eq!(cursor.as_mut().write(&[3, 4]).await.unwrap(), 2);
+            assert!(cursor.as_mut().seek(SeekFrom::Current(-5)).await.is_err());
+            cursor.as_mut().set_position(1);
+            assert_eq!(cursor.as_mut().read(&mut buf).await.unwrap(), 3);
+        });
+        assert_eq!(buf[..3], [2, 3, 4]);
+        assert_eq!(observer.snapshot(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn forks_share_writes() {
+        let set = MultiCursor::new(b"0123456789".to_vec());
+        let (mut writer, mut near, mut far) = (Box::pin(set.fork()), Box::pin(set.fork()), Box::pin(set.fork()));
+        let (mut a, mut b) = ([0u8; 3], [0u8; 3]);
+        block_on(async {
+            near.as_mut().set_position(1);
+            far.as_mut().set_position(6);
+            writer.as_mut().seek(SeekFrom::Start(2)).await.unwrap();
+            writer.as_mut().write(b"ab").await.unwrap();
+            writer.as_mut().seek(SeekFrom::Start(7)).await.unwrap();
+            writer.as_mut().write(b"cd").await.unwrap();
+            near.as_mut().read(&mut a).await.unwrap();
+            far.as_mut().read(&mut b).await.unwrap();
+        });
+        assert_eq!((&a, &b), (b"1ab", b"6cd"));
+        assert_eq!((near.position(), far.position(), writer.position()), (4, 9, 9));
+
+        let Err(set) = set.into_inner() else { panic!("forks are still alive") };
+        drop((writer, near, far));
+        assert_eq!(set.into_inner().ok().unwrap(), b"01ab456cd9");
+    }
+
+    #[test]
+    fn repolled_wait_registers_once() {
+        let (cursor, observer) = PinCursor::shared(Vec::new());
+        struct Task;
+
+        impl std::task::Wake for Task {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let mut wait = Box::pin(observer.wait_for_len(1));
+        let waker = Waker::from(Arc::new(Task));
+        let mut cx = Context::from_waker(&waker);
+        for _ in 0..10 {
+            assert!(wait.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!(lock(&observer.shared).len_wakers.len(), 1);
+
+        let mut cursor = Box::pin(cursor);
+        assert_eq!(block_on(cursor.as_mut().write(b"x")).unwrap(), 1);
+        assert!(lock(&observer.shared).len_wakers.is_empty());
+        assert!(wait.as_mut().poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn observer_sees_growing_prefixes() {
+        let (cursor, observer) = PinCursor::shared(Vec::new());
+        let expected: Vec<u8> = (0..100).collect();
+        thread::scope(|s| {
+            s.spawn(|| block_on(async {
+                let mut cursor = Box::pin(cursor);
+                for chunk in expected.chunks(7) {
+                    assert_eq!(cursor.as_mut().write(chunk).await.unwrap(), chunk.len());
+                }
+            }));
+            s.spawn(|| block_on(async {
+                let mut seen = 0;
+                for target in (10..=100).step_by(10) {
+                    observer.wait_for_len(target).await;
+                    let snapshot = observer.snapshot();
+                    assert!(snapshot.len() >= seen.max(target));
+                    assert_eq!(snapshot, expected[..snapshot.len()]);
+                    seen = snapshot.len();
+                }
+            }));
+        });
+        assert_eq!(observer.len(), 100);
+    }
+}
@@ -0,0 +1,117 @@
+use std::future::{Future, poll_fn};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A `!Unpin` wrapper around any `Unpin` sink.
+    ///
+    /// This is the [`PinCursor`](crate::PinCursor) treatment for code that is generic over `Sink`.
+    pub struct PinSink<S> {
+        s: S,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<S> PinSink<S>
+    where S: Unpin
+{
+    pub fn wrap(s: S) -> Self {
+        Self { s, _p: PhantomPinned }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.s
+    }
+
+    pub fn into_inner(self) -> S {
+        self.s
+    }
+
+    /// Waits until the sink is ready, sends `item` and flushes it.
+    pub fn send<'a, Item>(mut self: Pin<&'a mut Self>, item: Item) -> impl Future<Output=Result<(), S::Error>> + 'a
+        where S: Sink<Item>,
+              Item: 'a
+    {
+        let mut item = Some(item);
+        poll_fn(move |cx| {
+            if item.is_some() {
+                ready!(Sink::<Item>::poll_ready(self.as_mut(), cx))?;
+                let item = item.take().expect("checked above");
+                self.as_mut().start_send(item)?;
+            }
+            Sink::<Item>::poll_flush(self.as_mut(), cx)
+        })
+    }
+
+    /// Flushes and closes the sink.
+    pub fn close<'a, Item>(mut self: Pin<&'a mut Self>) -> impl Future<Output=Result<(), S::Error>> + 'a
+        where S: Sink<Item>,
+              Item: 'a
+    {
+        poll_fn(move |cx| Sink::<Item>::poll_close(self.as_mut(), cx))
+    }
+}
+
+impl<S, Item> Sink<Item> for PinSink<S>
+    where S: Sink<Item> + Unpin
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(self.project().s).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), S::Error> {
+        Pin::new(self.project().s).start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(self.project().s).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(self.project().s).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task::block_on;
+    use futures::sink::{Drain, drain};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(PinSink<Drain<u32>>: Unpin);
+        assert_impl_all!(PinSink<Drain<u32>>: Sink<u32>);
+        assert_impl_all!(PinSink<Vec<u8>>: Sink<u8>);
+    }
+
+    #[test]
+    fn drain_everything() {
+        let mut sink = Box::pin(PinSink::wrap(drain()));
+        block_on(async {
+            sink.as_mut().send(1u32).await.unwrap();
+            sink.as_mut().send(2u32).await.unwrap();
+            sink.as_mut().close::<u32>().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn vec_in_order() {
+        let mut sink = Box::pin(PinSink::wrap(Vec::new()));
+        block_on(async {
+            for item in ["a", "b", "c"] {
+                sink.as_mut().send(item).await.unwrap();
+            }
+        });
+        assert_eq!(sink.get_ref(), &["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,210 @@
+use std::io::Result;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{Read, Write};
+use pin_project_lite::pin_project;
+
+use crate::FaultProfile;
+use crate::faults::Faults;
+
+pin_project! {
+    /// A `!Unpin` stand-in for a network connection with a scripted peer.
+    ///
+    /// Reads drain the `incoming` bytes given at construction, and writes append to
+    /// a separate outgoing buffer, which can be checked with [`outgoing`](MockSocket::outgoing).
+    /// Once the incoming bytes run out, reads return EOF, or hang forever with
+    /// [`hang_at_eof`](MockSocket::hang_at_eof), like a peer that stopped talking but did not
+    /// disconnect. Each direction can be given faults independently, just like on a
+    /// [`PinCursor`](crate::PinCursor).
+    pub struct MockSocket {
+        incoming: Vec<u8>,
+        read_pos: usize,
+        outgoing: Vec<u8>,
+        read_faults: Faults,
+        write_faults: Faults,
+        hang_at_eof: bool,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl MockSocket {
+    pub fn new(incoming: Vec<u8>) -> Self {
+        Self {
+            incoming,
+            read_pos: 0,
+            outgoing: Vec::new(),
+            read_faults: Faults::new("read"),
+            write_faults: Faults::new("write"),
+            hang_at_eof: false,
+            _p: PhantomPinned,
+        }
+    }
+
+    /// Makes reads return `Poll::Pending` forever, instead of EOF, once the incoming bytes run out.
+    pub fn hang_at_eof(mut self) -> Self {
+        self.hang_at_eof = true;
+        self
+    }
+
+    /// See [`PinCursor::with_read_schedule`](crate::PinCursor::with_read_schedule).
+    pub fn with_read_schedule(mut self, sizes: impl IntoIterator<Item=usize>) -> Self {
+        self.read_faults.set_schedule(sizes);
+        self
+    }
+
+    /// See [`PinCursor::with_write_schedule`](crate::PinCursor::with_write_schedule).
+    pub fn with_write_schedule(mut self, sizes: impl IntoIterator<Item=usize>) -> Self {
+        self.write_faults.set_schedule(sizes);
+        self
+    }
+
+    /// See [`PinCursor::fail_read_after`](crate::PinCursor::fail_read_after).
+    pub fn fail_read_after(mut self, bytes: u64) -> Self {
+        self.read_faults.set_fail_after(bytes);
+        self
+    }
+
+    /// See [`PinCursor::fail_write_after`](crate::PinCursor::fail_write_after).
+    pub fn fail_write_after(mut self, bytes: u64) -> Self {
+        self.write_faults.set_fail_after(bytes);
+        self
+    }
+
+    /// See [`PinCursor::stall_reads`](crate::PinCursor::stall_reads).
+    pub fn stall_reads(mut self, polls: u32) -> Self {
+        self.read_faults.set_stalls(polls);
+        self
+    }
+
+    /// See [`PinCursor::stall_writes`](crate::PinCursor::stall_writes).
+    pub fn stall_writes(mut self, polls: u32) -> Self {
+        self.write_faults.set_stalls(polls);
+        self
+    }
+
+    /// See [`PinCursor::with_random_faults`](crate::PinCursor::with_random_faults).
+    pub fn with_random_faults(mut self, seed: u64, profile: FaultProfile) -> Self {
+        self.read_faults.set_random(seed, profile);
+        self.write_faults.set_random(!seed, profile);
+        self
+    }
+
+    /// The incoming bytes that have not been read yet.
+    pub fn unread(&self) -> &[u8] {
+        &self.incoming[self.read_pos..]
+    }
+
+    /// Everything written so far.
+    pub fn outgoing(&self) -> &[u8] {
+        &self.outgoing
+    }
+
+    /// Returns the unread incoming bytes and everything written.
+    pub fn into_parts(mut self) -> (Vec<u8>, Vec<u8>) {
+        self.incoming.drain(..self.read_pos);
+        (self.incoming, self.outgoing)
+    }
+}
+
+impl Read for MockSocket {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let available = (this.incoming.len() - *this.read_pos).min(buf.len());
+        if available == 0 && *this.hang_at_eof {
+            return Poll::Pending;
+        }
+        let chunk = ready!(this.read_faults.poll_begin(cx, available))?;
+        buf[..chunk].copy_from_slice(&this.incoming[*this.read_pos..*this.read_pos + chunk]);
+        *this.read_pos += chunk;
+        this.read_faults.finish(chunk);
+        Poll::Ready(Ok(chunk))
+    }
+}
+
+impl Write for MockSocket {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let chunk = ready!(this.write_faults.poll_begin(cx, buf.len()))?;
+        this.outgoing.extend_from_slice(&buf[..chunk]);
+        this.write_faults.finish(chunk);
+        Poll::Ready(Ok(chunk))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use async_std::io::{BufReader, Seek};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(MockSocket: Unpin, Seek);
+        assert_impl_all!(MockSocket: Read, Write, Send);
+    }
+
+    // Greets the server, expects a banner with a session number, and acknowledges it.
+    async fn handshake<S: Read + Write + Unpin>(socket: S) -> Result<String> {
+        let mut socket = BufReader::new(socket);
+        socket.get_mut().write_all(b"HELLO\r\n").await?;
+        let mut banner = String::new();
+        socket.read_line(&mut banner).await?;
+        let session = banner.trim_end().trim_start_matches("WELCOME ").to_string();
+        socket.get_mut().write_all(format!("ACK {}\r\n", session).as_bytes()).await?;
+        Ok(session)
+    }
+
+    #[test]
+    fn handshake_transcript() {
+        let socket = MockSocket::new(b"WELCOME 42\r\n".to_vec())
+            .with_read_schedule(vec![1; 8])
+            .with_write_schedule(vec![3])
+            .stall_writes(1);
+        let mut socket = Box::pin(socket);
+        assert_eq!(block_on(handshake(&mut socket)).unwrap(), "42");
+        assert_eq!(socket.outgoing(), b"HELLO\r\nACK 42\r\n");
+        assert!(socket.unread().is_empty());
+    }
+
+    #[test]
+    fn hang_at_eof() {
+        let mut socket = Box::pin(MockSocket::new(vec![1]).hang_at_eof());
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 4];
+        assert!(matches!(socket.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(1))));
+        assert!(socket.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+    }
+
+    #[test]
+    fn independent_faults() {
+        let mut socket = Box::pin(MockSocket::new(vec![1, 2, 3]).fail_write_after(0));
+        let mut buf = [0u8; 2];
+        assert!(block_on(socket.write(&[1])).is_err());
+        assert_eq!(block_on(socket.read(&mut buf)).unwrap(), 2);
+        assert_eq!(socket.unread(), [3]);
+        assert!(socket.outgoing().is_empty());
+        assert_eq!(MockSocket::new(vec![4]).into_parts(), (vec![4], Vec::new()));
+    }
+}
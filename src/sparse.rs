@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::GapPolicy;
+
+/// The largest buffer that [`SparseCursor::to_vec`] agrees to allocate.
+const TO_VEC_LIMIT: u64 = 64 << 20;
+
+pin_project! {
+    /// A `!Unpin` cursor over a sparse buffer, which only stores the bytes actually written.
+    ///
+    /// Unwritten regions ("holes") read as zeroes, just like the gap `Vec<u8>` zero-fills when
+    /// written past its end, but take no memory, so writing 4 bytes at 1 GiB is cheap.
+    /// Overlapping and adjacent writes are merged into a single extent.
+    pub struct SparseCursor {
+        extents: Extents,
+        pos: u64,
+        gap_policy: GapPolicy,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+// Non-overlapping, non-adjacent runs of written bytes, keyed by their start.
+#[derive(Default)]
+struct Extents {
+    map: BTreeMap<u64, Vec<u8>>,
+    len: u64,
+}
+
+impl SparseCursor {
+    pub fn new() -> Self {
+        Self { extents: Extents::default(), pos: 0, gap_policy: GapPolicy::Sparse, _p: PhantomPinned }
+    }
+
+    /// Chooses what writes starting past the end do with the gap. The default,
+    /// [`GapPolicy::Sparse`], leaves it as a hole; [`GapPolicy::ZeroFill`] stores its zeroes,
+    /// like a `Vec<u8>` would.
+    pub fn gap_policy(mut self, policy: GapPolicy) -> Self {
+        self.gap_policy = policy;
+        self
+    }
+
+    /// The logical length: the end of the last write, holes included.
+    pub fn len(&self) -> u64 {
+        self.extents.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// How many bytes are actually stored.
+    pub fn allocated_bytes(&self) -> usize {
+        self.extents.map.values().map(Vec::len).sum()
+    }
+
+    /// How many contiguous runs of written bytes there are.
+    pub fn extent_count(&self) -> usize {
+        self.extents.map.len()
+    }
+
+    /// The whole buffer, holes filled with zeroes.
+    ///
+    /// Fails with `ErrorKind::OutOfMemory` if it is longer than 64 MiB.
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        if self.len() > TO_VEC_LIMIT {
+            return Err(Error::new(ErrorKind::OutOfMemory, "sparse buffer is too large to be copied"));
+        }
+        let mut out = vec![0u8; self.len() as usize];
+        for (&start, data) in &self.extents.map {
+            out[start as usize..start as usize + data.len()].copy_from_slice(data);
+        }
+        Ok(out)
+    }
+}
+
+impl Extents {
+    fn read_at(&self, pos: u64, buf: &mut [u8]) -> usize {
+        let n = self.len.saturating_sub(pos).min(buf.len() as u64) as usize;
+        let buf = &mut buf[..n];
+        buf.fill(0);
+        let end = pos + n as u64;
+        let before = self.map.range(..pos).next_back();
+        for (&start, data) in before.into_iter().chain(self.map.range(pos..end)) {
+            let from = start.max(pos);
+            let to = (start + data.len() as u64).min(end);
+            if from < to {
+                let dst = (from - pos) as usize..(to - pos) as usize;
+                let src = (from - start) as usize..(to - start) as usize;
+                buf[dst].copy_from_slice(&data[src]);
+            }
+        }
+        n
+    }
+
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<()> {
+        let end = pos.checked_add(buf.len() as u64).ok_or_else(|| Error::new(
+            ErrorKind::InvalidInput,
+            "write would overflow the position",
+        ))?;
+        let touching: Vec<u64> = self.map.range(..=end)
+            .rev()
+            .take_while(|(&start, data)| start + data.len() as u64 >= pos)
+            .map(|(&start, _)| start)
+            .collect();
+        let start = touching.last().map_or(pos, |&first| first.min(pos));
+        let mut merged = Vec::new();
+        for old_start in touching.into_iter().rev() {
+            let old = self.map.remove(&old_start).expect("extent was just found");
+            let offset = (old_start - start) as usize;
+            merged.resize(merged.len().max(offset + old.len()), 0);
+            merged[offset..offset + old.len()].copy_from_slice(&old);
+        }
+        let offset = (pos - start) as usize;
+        merged.resize(merged.len().max(offset + buf.len()), 0);
+        merged[offset..offset + buf.len()].copy_from_slice(buf);
+        self.map.insert(start, merged);
+        self.len = self.len.max(end);
+        Ok(())
+    }
+}
+
+impl Default for SparseCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for SparseCursor {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let n = this.extents.read_at(*this.pos, buf);
+        *this.pos += n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Write for SparseCursor {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let this = self.project();
+        let len = this.extents.len;
+        this.gap_policy.check(*this.pos, len)?;
+        if *this.gap_policy == GapPolicy::ZeroFill && *this.pos > len {
+            this.extents.write_at(len, &vec![0; (*this.pos - len) as usize])?;
+        }
+        this.extents.write_at(*this.pos, buf)?;
+        *this.pos += buf.len() as u64;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Seek for SparseCursor {
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.project();
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                *this.pos = n;
+                return Poll::Ready(Ok(n));
+            }
+            SeekFrom::End(n) => (this.extents.len, n),
+            SeekFrom::Current(n) => (*this.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                *this.pos = n;
+                Poll::Ready(Ok(n))
+            }
+            None => Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(SparseCursor: Unpin);
+        assert_impl_all!(SparseCursor: Read, Write, Seek, Send);
+    }
+
+    #[test]
+    fn far_apart_writes() {
+        let mut cursor = Box::pin(SparseCursor::new());
+        let mut buf = [0xffu8; 6];
+        block_on(async {
+            cursor.write_all(b"head").await.unwrap();
+            cursor.seek(SeekFrom::Start(1 << 30)).await.unwrap();
+            cursor.write_all(b"tail").await.unwrap();
+            cursor.seek(SeekFrom::Start((1 << 30) - 2)).await.unwrap();
+            cursor.read_exact(&mut buf).await.unwrap();
+        });
+        assert_eq!(&buf, b"\0\0tail");
+        assert_eq!(cursor.len(), (1 << 30) + 4);
+        assert_eq!(cursor.allocated_bytes(), 8);
+        assert_eq!(cursor.extent_count(), 2);
+        assert_eq!(cursor.to_vec().unwrap_err().kind(), ErrorKind::OutOfMemory);
+    }
+
+    #[test]
+    fn merges_touching_extents() {
+        let mut cursor = Box::pin(SparseCursor::new());
+        block_on(async {
+            cursor.seek(SeekFrom::Start(2)).await.unwrap();
+            cursor.write_all(b"cd").await.unwrap();
+            cursor.seek(SeekFrom::Start(6)).await.unwrap();
+            cursor.write_all(b"gh").await.unwrap();
+            assert_eq!(cursor.extent_count(), 2);
+            cursor.seek(SeekFrom::Start(3)).await.unwrap();
+            cursor.write_all(b"DEF").await.unwrap();
+        });
+        assert_eq!(cursor.extent_count(), 1);
+        assert_eq!(cursor.allocated_bytes(), 6);
+        assert_eq!(cursor.to_vec().unwrap(), b"\0\0cDEFgh");
+    }
+
+    #[test]
+    fn gap_policies() {
+        for policy in [GapPolicy::Sparse, GapPolicy::ZeroFill, GapPolicy::Error] {
+            let mut cursor = Box::pin(SparseCursor::new().gap_policy(policy));
+            let result = block_on(async {
+                cursor.write_all(b"ab").await.unwrap();
+                cursor.seek(SeekFrom::Start(6)).await.unwrap();
+                cursor.write(b"gh").await
+            });
+            match policy {
+                GapPolicy::Sparse => {
+                    assert_eq!(result.unwrap(), 2);
+                    assert_eq!((cursor.extent_count(), cursor.allocated_bytes()), (2, 4));
+                }
+                GapPolicy::ZeroFill => {
+                    assert_eq!(result.unwrap(), 2);
+                    assert_eq!((cursor.extent_count(), cursor.allocated_bytes()), (1, 8));
+                }
+                GapPolicy::Error => {
+                    let err = result.unwrap_err();
+                    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+                    assert!(err.to_string().contains("gap of 4 bytes"), "{}", err);
+                    assert_eq!((cursor.len(), cursor.position()), (2, 6));
+                    continue;
+                }
+            }
+            assert_eq!(cursor.to_vec().unwrap(), b"ab\0\0\0\0gh");
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::fmt;
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor};
+
+const READ: usize = 0;
+const WRITE: usize = 1;
+
+struct Inner<T> {
+    cursor: Pin<Box<PinCursor<T>>>,
+    // Whether the last operation of each half returned `Poll::Pending`, and so is still in flight.
+    in_flight: [bool; 2],
+}
+
+type Shared<T> = Arc<Mutex<Inner<T>>>;
+
+fn lock<T>(shared: &Shared<T>) -> MutexGuard<'_, Inner<T>> {
+    // A panic while holding the lock cannot leave the cursor in an inconsistent state.
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl<T> Inner<T> {
+    // Runs `f` on the cursor for the given half, with the cursor's position swapped
+    // for the half's own one.
+    fn run<R>(&mut self, half: usize, pos: &mut u64, f: impl FnOnce(Pin<&mut PinCursor<T>>) -> Poll<R>) -> Poll<R> {
+        self.cursor.as_mut().set_position_unchecked(*pos);
+        let result = f(self.cursor.as_mut());
+        *pos = self.cursor.position();
+        self.in_flight[half] = result.is_pending();
+        result
+    }
+}
+
+fn with_cursor<T, R>(shared: &Shared<T>, half: usize, pos: &mut u64, f: impl FnOnce(Pin<&mut PinCursor<T>>) -> Poll<R>) -> Poll<R> {
+    lock(shared).run(half, pos, f)
+}
+
+fn poll_seek<T>(shared: &Shared<T>, half: usize, pos: &mut u64, cx: &mut Context<'_>, target: SeekFrom) -> Poll<Result<u64>>
+    where T: AsRef<[u8]>
+{
+    let mut inner = lock(shared);
+    if inner.in_flight[1 - half] {
+        return Poll::Ready(Err(Error::new(
+            ErrorKind::ResourceBusy,
+            "cannot seek while the other half has an operation in flight",
+        )));
+    }
+    inner.run(half, pos, |c| c.poll_seek(cx, target))
+}
+
+pin_project! {
+    /// The reading half of a [`PinCursor`], created by [`split`](PinCursor::split).
+    pub struct ReadHalf<T> {
+        shared: Shared<T>,
+        pos: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+pin_project! {
+    /// The writing half of a [`PinCursor`], created by [`split`](PinCursor::split).
+    pub struct WriteHalf<T> {
+        shared: Shared<T>,
+        pos: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> PinCursor<T> {
+    /// Splits the cursor into a reading half and a writing half that share the buffer.
+    ///
+    /// Each half has its own position, starting at the cursor's current one, so a reader
+    /// can follow behind a writer, as in an echo server. Both halves can seek, which moves
+    /// only their own position. The halves can be used concurrently from different tasks:
+    /// each operation locks the shared cursor for the duration of a single poll.
+    ///
+    /// Fault injection state is shared too, so while one half has an operation in flight
+    /// (its last poll returned `Poll::Pending`), seeks on the other half fail with
+    /// `ErrorKind::ResourceBusy`. An operation stays in flight until its half is polled again.
+    pub fn split(self: Pin<Box<Self>>) -> (ReadHalf<T>, WriteHalf<T>) {
+        let pos = self.position();
+        let shared = Arc::new(Mutex::new(Inner { cursor: self, in_flight: [false; 2] }));
+        (
+            ReadHalf { shared: shared.clone(), pos, _p: PhantomPinned },
+            WriteHalf { shared, pos, _p: PhantomPinned },
+        )
+    }
+}
+
+impl<T> ReadHalf<T> {
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl<T> ReadHalf<T> {
+    /// Rejoins the halves created by one [`split`](PinCursor::split) call.
+    ///
+    /// The rejoined cursor is positioned where the read half was. Fails, giving both halves back,
+    /// if they came from different cursors.
+    pub fn unsplit(self: Pin<Box<Self>>, write: Pin<Box<WriteHalf<T>>>) -> std::result::Result<Pin<Box<PinCursor<T>>>, UnsplitError<T>> {
+        if !Arc::ptr_eq(&self.shared, &write.shared) {
+            return Err(UnsplitError { read: self, write });
+        }
+        let pos = self.pos;
+        let shared = self.shared.clone();
+        drop(self);
+        drop(write);
+        let mutex = Arc::try_unwrap(shared).ok().expect("both halves have been dropped");
+        let mut cursor = mutex.into_inner().unwrap_or_else(|e| e.into_inner()).cursor;
+        cursor.as_mut().set_position_unchecked(pos);
+        Ok(cursor)
+    }
+}
+
+/// The error returned by [`ReadHalf::unsplit`] for halves of different cursors.
+pub struct UnsplitError<T> {
+    pub read: Pin<Box<ReadHalf<T>>>,
+    pub write: Pin<Box<WriteHalf<T>>>,
+}
+
+impl<T> fmt::Debug for UnsplitError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnsplitError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for UnsplitError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("tried to unsplit halves of two different cursors")
+    }
+}
+
+impl<T> std::error::Error for UnsplitError<T> {}
+
+impl<T> WriteHalf<T> {
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl<T> Read for ReadHalf<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        with_cursor(this.shared, READ, this.pos, |c| c.poll_read(cx, buf))
+    }
+}
+
+impl<T> Seek for ReadHalf<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.project();
+        poll_seek(this.shared, READ, this.pos, cx, pos)
+    }
+}
+
+impl<T> Write for WriteHalf<T>
+    where T: Buffer
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        with_cursor(this.shared, WRITE, this.pos, |c| c.poll_write(cx, buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.project();
+        with_cursor(this.shared, WRITE, this.pos, |c| c.poll_flush(cx))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.project();
+        with_cursor(this.shared, WRITE, this.pos, |c| c.poll_close(cx))
+    }
+}
+
+impl<T> Seek for WriteHalf<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.project();
+        poll_seek(this.shared, WRITE, this.pos, cx, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use async_std::io::Cursor;
+    use async_std::io::prelude::*;
+    use async_std::task::{block_on, spawn, yield_now};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(ReadHalf<Vec<u8>>: Unpin, Write);
+        assert_not_impl_any!(WriteHalf<Vec<u8>>: Unpin, Read);
+        assert_impl_all!(ReadHalf<Vec<u8>>: Read, Seek, Send);
+        assert_impl_all!(WriteHalf<Vec<u8>>: Write, Seek, Send);
+    }
+
+    #[test]
+    fn concurrent_halves() {
+        let cursor = Box::pin(PinCursor::wrap(Cursor::new(Vec::new())));
+        let (reader, writer) = cursor.split();
+        let (mut reader, mut writer) = (Box::pin(reader), Box::pin(writer));
+        block_on(async {
+            let writer_task = spawn(async move {
+                for chunk in [&b"hello"[..], b", ", b"world"] {
+                    writer.write_all(chunk).await.unwrap();
+                    yield_now().await;
+                }
+                writer.position()
+            });
+            let mut received = Vec::new();
+            let mut buf = [0u8; 4];
+            while received.len() < 12 {
+                let n = reader.read(&mut buf).await.unwrap();
+                received.extend_from_slice(&buf[..n]);
+                if n == 0 {
+                    yield_now().await;
+                }
+            }
+            assert_eq!(received, b"hello, world");
+            assert_eq!(writer_task.await, 12);
+
+            reader.seek(SeekFrom::Start(7)).await.unwrap();
+            let mut tail = Vec::new();
+            reader.read_to_end(&mut tail).await.unwrap();
+            assert_eq!(tail, b"world");
+        });
+    }
+
+    #[test]
+    fn unsplit() {
+        let cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])));
+        let (reader, writer) = cursor.split();
+        let (mut reader, mut writer) = (Box::pin(reader), Box::pin(writer));
+        let mut buf = [0u8; 1];
+        block_on(async {
+            writer.seek(SeekFrom::End(0)).await.unwrap();
+            writer.write_all(&[4]).await.unwrap();
+            reader.read_exact(&mut buf).await.unwrap();
+        });
+        let mut cursor = reader.unsplit(writer).unwrap();
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(cursor.get_ref(), &[1, 2, 3, 4]);
+        let mut rest = [0u8; 3];
+        assert_eq!(block_on(cursor.as_mut().read(&mut rest)).unwrap(), 3);
+        assert_eq!(rest, [2, 3, 4]);
+        assert_eq!(block_on(cursor.as_mut().write(&[5])).unwrap(), 1);
+        assert_eq!(cursor.get_ref(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn no_seek_during_pending_read() {
+        let cursor = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8, 2, 3])).stall_reads(1));
+        let (reader, writer) = cursor.split();
+        let (mut reader, mut writer) = (Box::pin(reader), Box::pin(writer));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [0u8; 3];
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        match writer.as_mut().poll_seek(&mut cx, SeekFrom::End(0)) {
+            Poll::Ready(Err(e)) => assert_eq!(e.kind(), ErrorKind::ResourceBusy),
+            _ => panic!("seek during an in-flight read was allowed"),
+        }
+        assert!(matches!(reader.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(3))));
+        assert!(matches!(writer.as_mut().poll_seek(&mut cx, SeekFrom::End(0)), Poll::Ready(Ok(3))));
+        assert_eq!(writer.position(), 3);
+    }
+
+    #[test]
+    fn unsplit_mismatched() {
+        let (reader_a, writer_a) = Box::pin(PinCursor::wrap(Cursor::new(vec![1u8]))).split();
+        let (reader_b, writer_b) = Box::pin(PinCursor::wrap(Cursor::new(vec![2u8]))).split();
+        let err = match Box::pin(reader_a).unsplit(Box::pin(writer_b)) {
+            Ok(_) => panic!("halves of different cursors were rejoined"),
+            Err(err) => err,
+        };
+        assert_eq!(err.to_string(), "tried to unsplit halves of two different cursors");
+        // The halves can still be matched up correctly afterwards.
+        assert!(err.read.unsplit(Box::pin(writer_a)).is_ok());
+        assert!(Box::pin(reader_b).unsplit(err.write).is_ok());
+    }
+}
@@ -0,0 +1,11 @@
+/// Counters collected by a [`PinCursor`](crate::PinCursor) since it was created, or since
+/// the last [`reset_counters`](crate::PinCursor::reset_counters).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// Reads that returned at least one byte.
+    pub read_calls: u64,
+    /// Writes that accepted at least one byte.
+    pub write_calls: u64,
+}
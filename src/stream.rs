@@ -0,0 +1,172 @@
+use std::future::{Future, poll_fn};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::io::Result;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::Read;
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::PinCursor;
+
+pin_project! {
+    /// A `!Unpin` wrapper around any `Unpin` stream.
+    ///
+    /// This is the [`PinCursor`](crate::PinCursor) treatment for code that is generic over `Stream`.
+    pub struct PinStream<S> {
+        s: S,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<S> PinStream<S>
+    where S: Stream + Unpin
+{
+    pub fn wrap(s: S) -> Self {
+        Self { s, _p: PhantomPinned }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.s
+    }
+
+    pub fn into_inner(self) -> S {
+        self.s
+    }
+
+    /// Resolves to the next item, or `None` once the stream has ended.
+    pub fn next(mut self: Pin<&mut Self>) -> impl Future<Output=Option<S::Item>> + '_ {
+        poll_fn(move |cx| self.as_mut().poll_next(cx))
+    }
+}
+
+impl<S> Stream for PinStream<S>
+    where S: Stream + Unpin
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        Pin::new(self.project().s).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.s.size_hint()
+    }
+}
+
+impl<T> PinCursor<T>
+    where T: AsRef<[u8]>
+{
+    /// Reads the rest of the cursor one byte at a time, yielding each byte together with
+    /// the position it was read from.
+    ///
+    /// The stream ends at EOF. Every byte is a separate read, subject to fault injection.
+    pub fn trace(self: Pin<&mut Self>) -> impl Stream<Item=Result<(u64, u8)>> + '_ {
+        Trace { cursor: self }
+    }
+}
+
+struct Trace<'a, T> {
+    cursor: Pin<&'a mut PinCursor<T>>,
+}
+
+impl<T> Stream for Trace<'_, T>
+    where T: AsRef<[u8]>
+{
+    type Item = Result<(u64, u8)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pos = self.cursor.position();
+        let mut byte = [0u8];
+        Poll::Ready(match ready!(self.cursor.as_mut().poll_read(cx, &mut byte)) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok((pos, byte[0]))),
+            Err(e) => Some(Err(e)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::SeekFrom;
+
+    use async_std::io::Cursor;
+    use async_std::task::block_on;
+    use futures::StreamExt;
+    use futures::stream::{self, Iter};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    type Numbers = Iter<std::vec::IntoIter<u32>>;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(PinStream<Numbers>: Unpin);
+        assert_impl_all!(PinStream<Numbers>: Stream);
+    }
+
+    #[test]
+    fn iter_order() {
+        let mut s = Box::pin(PinStream::wrap(stream::iter(vec![1u32, 2, 3])));
+        assert_eq!(s.size_hint(), (3, Some(3)));
+        block_on(async {
+            assert_eq!(s.as_mut().next().await, Some(1));
+            assert_eq!(s.as_mut().next().await, Some(2));
+            assert_eq!(s.as_mut().next().await, Some(3));
+            assert_eq!(s.as_mut().next().await, None);
+        });
+    }
+
+    // Yields 0..count, returning `Poll::Pending` before every item.
+    struct Reluctant {
+        next: u32,
+        count: u32,
+        stalled: bool,
+    }
+
+    impl Stream for Reluctant {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+            if self.next == self.count {
+                return Poll::Ready(None);
+            }
+            if !self.stalled {
+                self.stalled = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.stalled = false;
+            self.next += 1;
+            Poll::Ready(Some(self.next - 1))
+        }
+    }
+
+    #[test]
+    fn pending_heavy() {
+        let mut s = Box::pin(PinStream::wrap(Reluctant { next: 0, count: 4, stalled: false }));
+        let mut items = Vec::new();
+        block_on(async {
+            while let Some(item) = s.as_mut().next().await {
+                items.push(item);
+            }
+        });
+        assert_eq!(items, [0, 1, 2, 3]);
+        assert_eq!(s.get_ref().next, 4);
+    }
+
+    #[test]
+    fn trace_across_seek() {
+        let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(b"abcdef".to_vec())));
+        let mut trace = Vec::new();
+        block_on(async {
+            trace.extend(cursor.as_mut().trace().take(2).map(Result::unwrap).collect::<Vec<_>>().await);
+            cursor.as_mut().seek(SeekFrom::Start(4)).await.unwrap();
+            trace.extend(cursor.as_mut().trace().map(Result::unwrap).collect::<Vec<_>>().await);
+        });
+        assert_eq!(trace, [(0, b'a'), (1, b'b'), (4, b'e'), (5, b'f')]);
+    }
+}
@@ -0,0 +1,116 @@
+use std::io::{Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor};
+
+pin_project! {
+    /// A `!Unpin` writer that copies everything written to a cursor into a transcript.
+    ///
+    /// Writes go to the primary cursor, and exactly the bytes it accepts are then appended
+    /// to the secondary vector. Seeks move only the primary, so the secondary holds every
+    /// written byte in the order of writing, including ones that were later overwritten.
+    pub struct TeeCursor<T> {
+        primary: Pin<Box<PinCursor<T>>>,
+        secondary: Vec<u8>,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> TeeCursor<T>
+    where T: Buffer
+{
+    pub fn new(primary: PinCursor<T>, secondary: Vec<u8>) -> Self {
+        Self { primary: Box::pin(primary), secondary, _p: PhantomPinned }
+    }
+
+    pub fn primary(&self) -> &PinCursor<T> {
+        &self.primary
+    }
+
+    /// Everything written so far, in append order.
+    pub fn secondary(&self) -> &[u8] {
+        &self.secondary
+    }
+
+    pub fn into_parts(self: Pin<Box<Self>>) -> (Pin<Box<PinCursor<T>>>, Vec<u8>) {
+        // SAFETY: nothing in a TeeCursor depends on its address, and the primary cursor
+        // stays pinned in its own box.
+        let this = unsafe { Pin::into_inner_unchecked(self) };
+        (this.primary, this.secondary)
+    }
+}
+
+impl<T> Write for TeeCursor<T>
+    where T: Buffer
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let n = ready!(this.primary.as_mut().poll_write(cx, buf))?;
+        this.secondary.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().primary.as_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().primary.as_mut().poll_close(cx)
+    }
+}
+
+impl<T> Seek for TeeCursor<T>
+    where T: Buffer
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        self.project().primary.as_mut().poll_seek(cx, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::{Cursor, Read};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(TeeCursor<Vec<u8>>: Unpin, Read);
+        assert_impl_all!(TeeCursor<Vec<u8>>: Write, Seek);
+    }
+
+    #[test]
+    fn overwrite_keeps_transcript() {
+        let mut tee = Box::pin(TeeCursor::new(PinCursor::wrap(Cursor::new(Vec::new())), Vec::new()));
+        block_on(async {
+            tee.write_all(b"hello world").await.unwrap();
+            tee.seek(SeekFrom::Start(6)).await.unwrap();
+            tee.write_all(b"there").await.unwrap();
+        });
+        let (primary, secondary) = tee.into_parts();
+        assert_eq!(primary.bytes_ref(), b"hello there");
+        assert_eq!(secondary, b"hello worldthere");
+    }
+
+    #[test]
+    fn short_writes_mirror_accepted_prefix() {
+        let primary = PinCursor::from_array([0u8; 4]).with_write_schedule(vec![3]);
+        let mut tee = Box::pin(TeeCursor::new(primary, b">".to_vec()));
+        block_on(async {
+            assert_eq!(tee.write(b"abcdef").await.unwrap(), 3);
+            assert_eq!(tee.write(b"def").await.unwrap(), 1);
+            assert!(tee.write_all(b"g").await.is_err());
+        });
+        assert_eq!(tee.secondary(), b">abcd");
+        assert_eq!(tee.primary().bytes_ref(), b"abcd");
+    }
+}
@@ -0,0 +1,212 @@
+use std::future::Future;
+use std::io::{IoSlice, IoSliceMut, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, Instant};
+
+use async_std::io::{Read, Write};
+use async_std::task::sleep;
+use pin_project_lite::pin_project;
+
+type Delay = Pin<Box<dyn Future<Output=()> + Send>>;
+
+// A token bucket for one direction: one token is one byte.
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled: Instant,
+    delay: Option<Delay>,
+}
+
+impl Bucket {
+    fn new(bytes_per_sec: u64) -> Self {
+        let rate = bytes_per_sec.max(1) as f64;
+        Self { rate, burst: rate, tokens: rate, refilled: Instant::now(), delay: None }
+    }
+
+    fn set_burst(&mut self, bytes: u64) {
+        self.burst = bytes.max(1) as f64;
+        self.tokens = self.burst;
+    }
+
+    // Waits until at least one byte may pass, then returns how many of `len` may.
+    fn poll_take(&mut self, cx: &mut Context<'_>, len: usize) -> Poll<usize> {
+        loop {
+            let now = Instant::now();
+            let elapsed = now.duration_since(self.refilled).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+            self.refilled = now;
+            if self.tokens >= 1.0 {
+                // A delay left over from an abandoned operation is of no use anymore.
+                self.delay = None;
+                return Poll::Ready(len.min(self.tokens as usize));
+            }
+            let rate = self.rate;
+            let missing = 1.0 - self.tokens;
+            let delay = self.delay.get_or_insert_with(|| Box::pin(sleep(Duration::from_secs_f64(missing / rate))));
+            ready!(delay.as_mut().poll(cx));
+            self.delay = None;
+        }
+    }
+
+    fn spend(&mut self, n: usize) {
+        self.tokens -= n as f64;
+    }
+}
+
+pin_project! {
+    /// A `!Unpin` wrapper that limits the throughput of reads and writes to a number of bytes
+    /// per second, for testing timeouts against a slow peer.
+    ///
+    /// Each direction has its own token bucket, which starts full and holds at most one second
+    /// worth of bytes, unless [`with_burst`](ThrottledCursor::with_burst) says otherwise.
+    /// Operations that find the bucket empty wait on an async-std timer. Tokens are only spent
+    /// on completed operations, so an operation that is dropped while waiting costs nothing.
+    pub struct ThrottledCursor<I> {
+        #[pin]
+        inner: I,
+        read_bucket: Bucket,
+        write_bucket: Bucket,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<I> ThrottledCursor<I> {
+    pub fn new(inner: I, bytes_per_sec: u64) -> Self {
+        Self {
+            inner,
+            read_bucket: Bucket::new(bytes_per_sec),
+            write_bucket: Bucket::new(bytes_per_sec),
+            _p: PhantomPinned,
+        }
+    }
+
+    /// Sets how many bytes each direction can transfer at once after being idle.
+    pub fn with_burst(mut self, bytes: u64) -> Self {
+        self.read_bucket.set_burst(bytes);
+        self.write_bucket.set_burst(bytes);
+        self
+    }
+
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    pub fn get_mut(self: Pin<&mut Self>) -> Pin<&mut I> {
+        self.project().inner
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I> Read for ThrottledCursor<I>
+    where I: Read
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return this.inner.poll_read(cx, buf);
+        }
+        let allowed = ready!(this.read_bucket.poll_take(cx, buf.len()));
+        let n = ready!(this.inner.poll_read(cx, &mut buf[..allowed]))?;
+        this.read_bucket.spend(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_read_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
+        match bufs.iter_mut().find(|b| !b.is_empty()) {
+            Some(buf) => self.poll_read(cx, buf),
+            None => Poll::Ready(Ok(0)),
+        }
+    }
+}
+
+impl<I> Write for ThrottledCursor<I>
+    where I: Write
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        if buf.is_empty() {
+            return this.inner.poll_write(cx, buf);
+        }
+        let allowed = ready!(this.write_bucket.poll_take(cx, buf.len()));
+        let n = ready!(this.inner.poll_write(cx, &buf[..allowed]))?;
+        this.write_bucket.spend(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<Result<usize>> {
+        match bufs.iter().find(|b| !b.is_empty()) {
+            Some(buf) => self.poll_write(cx, buf),
+            None => Poll::Ready(Ok(0)),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use async_std::io::Cursor;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use crate::{NullCursor, PinCursor, ZeroCursor};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(ThrottledCursor<Cursor<Vec<u8>>>: Unpin);
+        assert_impl_all!(ThrottledCursor<PinCursor<Vec<u8>>>: Read, Write, Send);
+    }
+
+    #[test]
+    fn reads_are_paced() {
+        let data: Vec<u8> = (0..=255).cycle().take(300).collect();
+        let cursor = PinCursor::wrap(Cursor::new(data.clone()));
+        let mut throttled = Box::pin(ThrottledCursor::new(cursor, 1000).with_burst(100));
+        let started = Instant::now();
+        let mut out = Vec::new();
+        block_on(throttled.read_to_end(&mut out)).unwrap();
+        assert_eq!(out, data);
+        // The burst covers 100 bytes, the other 200 take at least 0.2 s.
+        assert!(started.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[test]
+    fn writes_are_paced() {
+        let mut throttled = Box::pin(ThrottledCursor::new(NullCursor::new(), 2000).with_burst(10));
+        let started = Instant::now();
+        block_on(throttled.write_all(&[0; 210])).unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(95));
+        assert_eq!(throttled.get_ref().bytes_written(), 210);
+    }
+
+    #[test]
+    fn abandoned_wait_costs_nothing() {
+        let mut throttled = Box::pin(ThrottledCursor::new(ZeroCursor::new(), 100).with_burst(1));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buf = [1u8; 4];
+        assert!(matches!(throttled.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(1))));
+        // The bucket is empty now; this read starts waiting and is then abandoned.
+        assert!(throttled.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(matches!(throttled.as_mut().poll_read(&mut cx, &mut buf), Poll::Ready(Ok(1))));
+        assert_eq!(throttled.get_ref().bytes_read(), 2);
+    }
+}
@@ -0,0 +1,280 @@
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor};
+
+/// What reads from a [`TransactionalCursor`] see while a transaction is open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    /// Reads see the buffer as it would be after a commit.
+    Staged,
+    /// Reads see only what has been committed.
+    Committed,
+}
+
+pin_project! {
+    /// A `!Unpin` cursor whose writes can be staged and then committed or rolled back.
+    ///
+    /// Outside a transaction, all IO goes straight to the wrapped cursor. Between
+    /// [`begin`](TransactionalCursor::begin) and [`commit`](TransactionalCursor::commit) or
+    /// [`rollback`](TransactionalCursor::rollback), writes are recorded along with their offsets
+    /// and only advance the position; reads see them or not depending on the [`Visibility`].
+    /// Seeks relative to the end always use the length of the committed buffer.
+    pub struct TransactionalCursor<T> {
+        inner: Pin<Box<PinCursor<T>>>,
+        staged: Option<Vec<(u64, Vec<u8>)>>,
+        visibility: Visibility,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> TransactionalCursor<T>
+    where T: Buffer
+{
+    /// Wraps `inner`, with reads seeing staged writes.
+    pub fn new(inner: PinCursor<T>) -> Self {
+        Self { inner: Box::pin(inner), staged: None, visibility: Visibility::Staged, _p: PhantomPinned }
+    }
+
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    pub fn get_ref(&self) -> &PinCursor<T> {
+        &self.inner
+    }
+
+    /// Returns the wrapped cursor. Writes still staged by an open transaction are discarded.
+    pub fn into_inner(self: Pin<Box<Self>>) -> Pin<Box<PinCursor<T>>> {
+        // SAFETY: nothing in a TransactionalCursor depends on its address, and the cursor
+        // it owns stays pinned in its own box.
+        unsafe { Pin::into_inner_unchecked(self) }.inner
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.staged.is_some()
+    }
+
+    /// Starts staging writes. Fails with `ErrorKind::Other` if a transaction is already open.
+    pub fn begin(self: Pin<&mut Self>) -> Result<()> {
+        let staged = self.project().staged;
+        if staged.is_some() {
+            return Err(Error::other("nested transactions are not supported"));
+        }
+        *staged = Some(Vec::new());
+        Ok(())
+    }
+
+    /// Applies the staged writes to the buffer atomically, and closes the transaction.
+    ///
+    /// This goes around the wrapped cursor's fault injection. The writes are first applied,
+    /// in order, to a copy of the buffer contents, which the buffer then checks with
+    /// [`Buffer::check_contents`]; if it would not take them, for example because they do not
+    /// fit into a fixed-size buffer (`ErrorKind::WriteZero`) or leave a `String` with invalid
+    /// UTF-8 (`ErrorKind::InvalidData`), the commit fails with the buffer untouched.
+    /// The transaction is closed either way.
+    pub fn commit(self: Pin<&mut Self>) -> Result<()> {
+        let this = self.project();
+        let staged = this.staged.take().ok_or_else(no_transaction)?;
+        let buffer = this.inner.as_mut().buffer_mut();
+        let mut contents = buffer.as_ref().to_vec();
+        for (pos, data) in staged {
+            contents.write_at(pos, &data)?;
+        }
+        buffer.check_contents(&contents)?;
+        if buffer.write_at(0, &contents)? < contents.len() {
+            return Err(Error::new(ErrorKind::WriteZero, "staged writes do not fit into the buffer"));
+        }
+        Ok(())
+    }
+
+    /// Discards the staged writes and closes the transaction. The position is left where it is.
+    pub fn rollback(self: Pin<&mut Self>) -> Result<()> {
+        self.project().staged.take().map(drop).ok_or_else(no_transaction)
+    }
+}
+
+fn no_transaction() -> Error {
+    Error::other("no transaction is open")
+}
+
+impl<T> Read for TransactionalCursor<T>
+    where T: Buffer
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let staged = match this.staged {
+            Some(staged) if *this.visibility == Visibility::Staged => staged,
+            _ => return this.inner.as_mut().poll_read(cx, buf),
+        };
+        let committed = this.inner.bytes_ref();
+        let len = staged.iter()
+            .map(|(pos, data)| pos.saturating_add(data.len() as u64))
+            .fold(committed.len() as u64, u64::max);
+        let start = this.inner.position();
+        let n = usize::try_from(len.saturating_sub(start)).unwrap_or(usize::MAX).min(buf.len());
+        let buf = &mut buf[..n];
+        buf.fill(0);
+        if let Some(from) = usize::try_from(start).ok().filter(|&s| s < committed.len()) {
+            let m = (committed.len() - from).min(n);
+            buf[..m].copy_from_slice(&committed[from..from + m]);
+        }
+        for (pos, data) in staged.iter() {
+            overlay(buf, start, *pos, data);
+        }
+        this.inner.as_mut().set_position_unchecked(start + n as u64);
+        Poll::Ready(Ok(n))
+    }
+}
+
+// Copies the part of `data`, written at `pos`, that overlaps `buf`, which starts at `start`.
+fn overlay(buf: &mut [u8], start: u64, pos: u64, data: &[u8]) {
+    let end = start + buf.len() as u64;
+    let from = pos.max(start);
+    let to = pos.saturating_add(data.len() as u64).min(end);
+    if from < to {
+        let (from, to) = ((from - start) as usize, (to - start) as usize);
+        let skip = (start.max(pos) - pos) as usize;
+        buf[from..to].copy_from_slice(&data[skip..skip + (to - from)]);
+    }
+}
+
+impl<T> Write for TransactionalCursor<T>
+    where T: Buffer
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.project();
+        let Some(staged) = this.staged else {
+            return this.inner.as_mut().poll_write(cx, buf);
+        };
+        let pos = this.inner.position();
+        let end = pos.checked_add(buf.len() as u64).ok_or_else(|| Error::new(
+            ErrorKind::InvalidInput,
+            "staged write would overflow the position",
+        ))?;
+        staged.push((pos, buf.to_vec()));
+        this.inner.as_mut().set_position_unchecked(end);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().inner.as_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().inner.as_mut().poll_close(cx)
+    }
+}
+
+impl<T> Seek for TransactionalCursor<T>
+    where T: Buffer
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        self.project().inner.as_mut().poll_seek(cx, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::Cursor;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(TransactionalCursor<Vec<u8>>: Unpin);
+        assert_impl_all!(TransactionalCursor<Vec<u8>>: Read, Write, Seek);
+    }
+
+    fn read_all<T: Buffer>(cursor: &mut Pin<Box<TransactionalCursor<T>>>) -> Vec<u8> {
+        let mut out = Vec::new();
+        block_on(async {
+            cursor.seek(SeekFrom::Start(0)).await.unwrap();
+            cursor.read_to_end(&mut out).await.unwrap();
+        });
+        out
+    }
+
+    #[test]
+    fn staged_visibility() {
+        let mut cursor = Box::pin(TransactionalCursor::new(PinCursor::wrap(Cursor::new(b"abcdef".to_vec()))));
+        cursor.as_mut().begin().unwrap();
+        assert!(cursor.as_mut().begin().is_err());
+        block_on(async {
+            cursor.seek(SeekFrom::Start(2)).await.unwrap();
+            cursor.write_all(b"XY").await.unwrap();
+            cursor.seek(SeekFrom::Start(5)).await.unwrap();
+            cursor.write_all(b"ZZZ").await.unwrap();
+        });
+        assert_eq!(read_all(&mut cursor), b"abXYeZZZ");
+        let mut buf = [0u8; 3];
+        block_on(async {
+            cursor.seek(SeekFrom::Start(3)).await.unwrap();
+            cursor.read_exact(&mut buf).await.unwrap();
+        });
+        assert_eq!(&buf, b"YeZ");
+        assert_eq!(cursor.get_ref().bytes_ref(), b"abcdef");
+        cursor.as_mut().commit().unwrap();
+        assert_eq!(cursor.get_ref().bytes_ref(), b"abXYeZZZ");
+        assert!(cursor.as_mut().commit().is_err());
+
+        cursor.as_mut().begin().unwrap();
+        block_on(cursor.write_all(b"!")).unwrap();
+        assert_eq!(cursor.into_inner().bytes_ref(), b"abXYeZZZ");
+    }
+
+    #[test]
+    fn committed_visibility_and_rollback() {
+        let inner = PinCursor::wrap(Cursor::new(b"abcdef".to_vec()));
+        let mut cursor = Box::pin(TransactionalCursor::new(inner).with_visibility(Visibility::Committed));
+        cursor.as_mut().begin().unwrap();
+        block_on(cursor.write_all(b"XYZ")).unwrap();
+        assert_eq!(cursor.get_ref().position(), 3);
+        assert_eq!(read_all(&mut cursor), b"abcdef");
+        cursor.as_mut().rollback().unwrap();
+        assert!(!cursor.in_transaction());
+        assert_eq!(read_all(&mut cursor), b"abcdef");
+
+        block_on(async {
+            cursor.seek(SeekFrom::Start(2)).await.unwrap();
+            cursor.write_all(b"12").await.unwrap();
+        });
+        assert_eq!(cursor.get_ref().bytes_ref(), b"ab12ef");
+    }
+
+    #[test]
+    fn commit_into_short_buffer() {
+        let mut cursor = Box::pin(TransactionalCursor::new(PinCursor::from_array([0u8; 4])));
+        cursor.as_mut().begin().unwrap();
+        block_on(async {
+            cursor.write_all(b"ab").await.unwrap();
+            cursor.write_all(b"cdef").await.unwrap();
+        });
+        assert_eq!(cursor.as_mut().commit().unwrap_err().kind(), ErrorKind::WriteZero);
+        assert_eq!(cursor.get_ref().bytes_ref(), [0u8; 4]);
+        assert!(!cursor.in_transaction());
+    }
+
+    #[test]
+    fn commit_invalid_utf8() {
+        let mut cursor = Box::pin(TransactionalCursor::new(PinCursor::wrap(Cursor::new(String::from("abc")))));
+        cursor.as_mut().begin().unwrap();
+        block_on(async {
+            cursor.write_all(b"x").await.unwrap();
+            cursor.write_all(&[0xff]).await.unwrap();
+        });
+        assert_eq!(cursor.as_mut().commit().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(cursor.get_ref().bytes_ref(), b"abc");
+    }
+}
@@ -0,0 +1,51 @@
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+
+use async_std::io::Cursor;
+
+use crate::{PinCursor, ops};
+
+/// Writes `data` to a fresh `PinCursor<Vec<u8>>`, rewinds it, reads everything back and checks
+/// that the same bytes came out.
+///
+/// A mismatch is reported as an `ErrorKind::InvalidData` error describing both sides.
+pub async fn verify_roundtrip(data: &[u8]) -> Result<()> {
+    roundtrip(PinCursor::wrap(Cursor::new(Vec::new())), data).await
+}
+
+async fn roundtrip(cursor: PinCursor<Vec<u8>>, data: &[u8]) -> Result<()> {
+    let mut cursor = Box::pin(cursor);
+    ops::write_all(cursor.as_mut(), data).await?;
+    cursor.as_mut().seek(SeekFrom::Start(0)).await?;
+    let mut read = Vec::with_capacity(data.len());
+    ops::read_to_end(cursor.as_mut(), &mut read).await?;
+    if read == data {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("round trip mismatch: wrote {} bytes {:?}, read back {} bytes {:?}", data.len(), data, read.len(), read),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task::block_on;
+
+    use super::*;
+
+    #[test]
+    fn sample_payload() {
+        block_on(verify_roundtrip(b"the quick brown fox")).unwrap();
+        block_on(verify_roundtrip(&[])).unwrap();
+    }
+
+    #[test]
+    fn mismatch() {
+        // A cursor with leftovers after the written data reads back more than was written.
+        let broken = PinCursor::wrap(Cursor::new(vec![9u8; 3]));
+        let err = block_on(roundtrip(broken, &[1])).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "round trip mismatch: wrote 1 bytes [1], read back 3 bytes [1, 9, 9]");
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::{Error, ErrorKind, Result, SeekFrom};
+use std::marker::PhantomPinned;
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use async_std::io::{Read, Seek, Write};
+use pin_project_lite::pin_project;
+
+use crate::{Buffer, PinCursor};
+
+pin_project! {
+    /// A `!Unpin` view of a byte range of a cursor, like a partition of a disk image.
+    ///
+    /// Created by [`PinCursor::window`]. Position 0 of the window is `range.start` of the cursor,
+    /// and no IO can reach outside the range: reads see EOF at its end, writes running past
+    /// the end are truncated (or rejected, see [`reject_overflow`](WindowCursor::reject_overflow)),
+    /// and seeks are clamped to it, with `SeekFrom::End` meaning the end of the window.
+    pub struct WindowCursor<T> {
+        inner: Pin<Box<PinCursor<T>>>,
+        range: Range<u64>,
+        pos: u64,
+        reject_overflow: bool,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<T> PinCursor<T> {
+    /// Restricts IO to the bytes of the cursor in `range`.
+    pub fn window(self: Pin<Box<Self>>, range: Range<u64>) -> WindowCursor<T> {
+        WindowCursor { inner: self, range, pos: 0, reject_overflow: false, _p: PhantomPinned }
+    }
+}
+
+impl<T> WindowCursor<T> {
+    /// Makes writes that do not fit into the window fail with `ErrorKind::InvalidInput`
+    /// instead of being truncated.
+    pub fn reject_overflow(mut self) -> Self {
+        self.reject_overflow = true;
+        self
+    }
+
+    /// Narrows the window further; `range` is relative to this window and is cut off at its end.
+    ///
+    /// The new window starts at position 0 and inherits [`reject_overflow`](WindowCursor::reject_overflow).
+    pub fn window(self: Pin<Box<Self>>, range: Range<u64>) -> WindowCursor<T> {
+        let this = Self::unbox(self);
+        let start = this.range.start.saturating_add(range.start).min(this.range.end);
+        let end = this.range.start.saturating_add(range.end).clamp(start, this.range.end);
+        WindowCursor { inner: this.inner, range: start..end, pos: 0, reject_overflow: this.reject_overflow, _p: PhantomPinned }
+    }
+
+    /// The range of the underlying cursor that this window covers.
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.range.end.saturating_sub(self.range.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The position within the window.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    pub fn get_ref(&self) -> &PinCursor<T> {
+        &self.inner
+    }
+
+    /// Returns the underlying cursor, at the position of the last IO through the window.
+    pub fn into_inner(self: Pin<Box<Self>>) -> Pin<Box<PinCursor<T>>> {
+        Self::unbox(self).inner
+    }
+
+    fn unbox(self: Pin<Box<Self>>) -> Self {
+        // SAFETY: nothing in a WindowCursor depends on its address, and the cursor it owns
+        // stays pinned in its own box.
+        *unsafe { Pin::into_inner_unchecked(self) }
+    }
+
+    // How many of `len` bytes fit between the position and the end of the window.
+    fn room(&self, len: usize) -> usize {
+        self.len().saturating_sub(self.pos).min(len as u64) as usize
+    }
+
+    // Moves the underlying cursor to where the window's position is.
+    fn inner_at_pos(self: Pin<&mut Self>) -> Pin<&mut PinCursor<T>> {
+        let this = self.project();
+        let mut inner = this.inner.as_mut();
+        inner.as_mut().set_position_unchecked(this.range.start + *this.pos);
+        inner
+    }
+}
+
+impl<T> Read for WindowCursor<T>
+    where T: AsRef<[u8]>
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let room = self.room(buf.len());
+        if room == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let n = ready!(self.as_mut().inner_at_pos().poll_read(cx, &mut buf[..room]))?;
+        *self.project().pos += n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T> Write for WindowCursor<T>
+    where T: Buffer
+{
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let room = self.room(buf.len());
+        if room < buf.len() && self.reject_overflow {
+            return Poll::Ready(Err(Error::new(ErrorKind::InvalidInput, "write would cross the end of the window")));
+        }
+        if room == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let n = ready!(self.as_mut().inner_at_pos().poll_write(cx, &buf[..room]))?;
+        *self.project().pos += n as u64;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().inner.as_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.project().inner.as_mut().poll_close(cx)
+    }
+}
+
+impl<T> Seek for WindowCursor<T>
+    where T: AsRef<[u8]>
+{
+    /// Never fails: targets before the start or after the end of the window are clamped to them.
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let len = self.len();
+        let this = self.project();
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => (n, 0),
+            SeekFrom::End(n) => (len, n),
+            SeekFrom::Current(n) => (*this.pos, n),
+        };
+        let target = if offset < 0 {
+            base.saturating_sub(offset.unsigned_abs())
+        } else {
+            base.saturating_add(offset as u64)
+        };
+        *this.pos = target.min(len);
+        Poll::Ready(Ok(*this.pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::Cursor;
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    fn disk() -> Pin<Box<PinCursor<Vec<u8>>>> {
+        Box::pin(PinCursor::wrap(Cursor::new((0..=255).collect())))
+    }
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(WindowCursor<Vec<u8>>: Unpin);
+        assert_impl_all!(WindowCursor<Vec<u8>>: Read, Write, Seek);
+    }
+
+    #[test]
+    fn reads_translated_and_bounded() {
+        let mut window = Box::pin(disk().window(16..20));
+        let mut out = Vec::new();
+        assert_eq!(block_on(window.read_to_end(&mut out)).unwrap(), 4);
+        assert_eq!(out, [16, 17, 18, 19]);
+        assert_eq!(block_on(window.seek(SeekFrom::End(-1))).unwrap(), 3);
+        assert_eq!(block_on(window.seek(SeekFrom::Current(100))).unwrap(), 4);
+        assert_eq!(block_on(window.seek(SeekFrom::Current(-100))).unwrap(), 0);
+    }
+
+    #[test]
+    fn writes_stay_inside() {
+        let mut window = Box::pin(disk().window(8..12));
+        block_on(async {
+            window.seek(SeekFrom::Start(2)).await.unwrap();
+            assert_eq!(window.write(&[0xaa; 5]).await.unwrap(), 2);
+            assert_eq!(window.write(&[0xbb]).await.unwrap(), 0);
+        });
+        let cursor = window.into_inner();
+        assert_eq!(cursor.bytes_ref()[8..14], [8, 9, 0xaa, 0xaa, 12, 13]);
+        assert_eq!(cursor.len(), 256);
+        assert_eq!(cursor.position(), 12);
+
+        let mut strict = Box::pin(disk().window(0..2).reject_overflow());
+        let err = block_on(strict.write(&[1, 2, 3])).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(strict.get_ref().bytes_ref()[..3], [0, 1, 2]);
+    }
+
+    #[test]
+    fn nested_windows() {
+        let mut outer = Box::pin(disk().window(100..200));
+        assert_eq!(block_on(outer.read(&mut [0u8; 4])).unwrap(), 4);
+        let mut inner = Box::pin(outer.window(10..1000));
+        assert_eq!(inner.range(), 110..200);
+        let mut buf = [0u8; 2];
+        block_on(async {
+            inner.seek(SeekFrom::End(-2)).await.unwrap();
+            inner.read_exact(&mut buf).await.unwrap();
+        });
+        assert_eq!(buf, [198, 199]);
+        let mut empty = Box::pin(Box::pin(disk().window(10..20)).window(30..40));
+        assert!(empty.is_empty());
+        assert_eq!(block_on(empty.read(&mut buf)).unwrap(), 0);
+    }
+}
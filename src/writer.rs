@@ -0,0 +1,121 @@
+use std::future::{Future, poll_fn};
+use std::io::{IoSlice, Result};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::Write;
+use pin_project_lite::pin_project;
+
+use crate::ops;
+
+pin_project! {
+    /// A `!Unpin` wrapper around any `Unpin` writer.
+    ///
+    /// This is the [`PinReader`](crate::PinReader) counterpart for writers,
+    /// such as `async_std::io::sink` or a socket fake.
+    pub struct PinWriter<W> {
+        w: W,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl<W> PinWriter<W>
+    where W: Write + Unpin
+{
+    pub fn wrap(w: W) -> Self {
+        Self { w, _p: PhantomPinned }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.w
+    }
+
+    pub fn get_mut(self: Pin<&mut Self>) -> &mut W {
+        self.project().w
+    }
+
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    pub fn write<'a, 'b>(mut self: Pin<&'a mut Self>, buf: &'b [u8]) -> impl Future<Output=Result<usize>> + use<'a, 'b, W> {
+        poll_fn(move |cx| self.as_mut().poll_write(cx, buf))
+    }
+
+    pub fn write_all<'a, 'b>(self: Pin<&'a mut Self>, buf: &'b [u8]) -> impl Future<Output=Result<()>> + use<'a, 'b, W> {
+        ops::write_all(self, buf)
+    }
+
+    pub fn flush(mut self: Pin<&mut Self>) -> impl Future<Output=Result<()>> + '_ {
+        poll_fn(move |cx| self.as_mut().poll_flush(cx))
+    }
+
+    pub fn close(mut self: Pin<&mut Self>) -> impl Future<Output=Result<()>> + '_ {
+        poll_fn(move |cx| self.as_mut().poll_close(cx))
+    }
+}
+
+impl<W> Write for PinWriter<W>
+    where W: Write + Unpin
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Pin::new(self.project().w).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<Result<usize>> {
+        Pin::new(self.project().w).poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(self.project().w).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(self.project().w).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::{Cursor, Sink, sink};
+    use async_std::io::prelude::*;
+    use async_std::task::block_on;
+    use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_all!(PinWriter<Sink>: Unpin);
+        assert_impl_all!(PinWriter<Sink>: Write);
+    }
+
+    #[test]
+    fn sink_writer() {
+        let mut writer = Box::pin(PinWriter::wrap(sink()));
+        block_on(async {
+            assert_eq!(writer.as_mut().write(&[1, 2, 3]).await.unwrap(), 3);
+            writer.as_mut().write_all(&[4; 100]).await.unwrap();
+            writer.as_mut().flush().await.unwrap();
+            writer.as_mut().close().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn matches_direct_cursor() {
+        let mut direct = Cursor::new(Vec::new());
+        let mut writer = Box::pin(PinWriter::wrap(Cursor::new(Vec::new())));
+        block_on(async {
+            direct.write_all(b"hello, ").await.unwrap();
+            direct.write(b"world").await.unwrap();
+            direct.flush().await.unwrap();
+            writer.as_mut().write_all(b"hello, ").await.unwrap();
+            writer.as_mut().write(b"world").await.unwrap();
+            writer.as_mut().flush().await.unwrap();
+        });
+        assert_eq!(writer.get_ref().get_ref(), direct.get_ref());
+        assert_eq!(writer.get_ref().position(), direct.position());
+    }
+}
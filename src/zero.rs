@@ -0,0 +1,101 @@
+use std::io::{IoSliceMut, Result};
+use std::marker::PhantomPinned;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_std::io::Read;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A `!Unpin` analogue of `/dev/zero`: an endless source of zero bytes.
+    ///
+    /// Reads fill the whole buffer, or at most [`with_cap`](ZeroCursor::with_cap) bytes of it,
+    /// and never return EOF. Reads into an empty buffer return `Ok(0)`; vectored reads fill
+    /// the buffers in order.
+    pub struct ZeroCursor {
+        cap: Option<NonZeroUsize>,
+        polls: u64,
+        bytes_read: u64,
+        #[pin]
+        _p: PhantomPinned
+    }
+}
+
+impl ZeroCursor {
+    pub fn new() -> Self {
+        Self { cap: None, polls: 0, bytes_read: 0, _p: PhantomPinned }
+    }
+
+    /// Makes every read return at most `cap` bytes. The cap cannot be zero, which would make
+    /// reads return EOF.
+    pub fn with_cap(mut self, cap: NonZeroUsize) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    /// How many times `poll_read` has been called, including calls with empty buffers.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+}
+
+impl Default for ZeroCursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for ZeroCursor {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        self.poll_read_vectored(cx, &mut [IoSliceMut::new(buf)])
+    }
+
+    fn poll_read_vectored(self: Pin<&mut Self>, _cx: &mut Context<'_>, bufs: &mut [IoSliceMut<'_>]) -> Poll<Result<usize>> {
+        let this = self.project();
+        *this.polls += 1;
+        let mut left = this.cap.map_or(usize::MAX, NonZeroUsize::get);
+        let mut n = 0;
+        for buf in bufs {
+            let chunk = buf.len().min(left);
+            buf[..chunk].fill(0);
+            left -= chunk;
+            n += chunk;
+        }
+        *this.bytes_read += n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use async_std::io::{Seek, Write};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+
+    #[test]
+    fn impls() {
+        assert_not_impl_any!(ZeroCursor: Unpin, Write, Seek);
+        assert_impl_all!(ZeroCursor: Read, Send);
+    }
+
+    #[test]
+    fn capped_vectored_reads() {
+        let mut zero = Box::pin(ZeroCursor::new().with_cap(NonZeroUsize::new(5).unwrap()));
+        let mut cx = Context::from_waker(Waker::noop());
+        let (mut a, mut b) = ([1u8; 3], [1u8; 3]);
+        let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+        assert!(matches!(zero.as_mut().poll_read_vectored(&mut cx, &mut bufs), Poll::Ready(Ok(5))));
+        assert_eq!((a, b), ([0; 3], [0, 0, 1]));
+        assert!(matches!(zero.as_mut().poll_read(&mut cx, &mut []), Poll::Ready(Ok(0))));
+        assert_eq!(zero.polls(), 2);
+        assert_eq!(zero.bytes_read(), 5);
+    }
+}
@@ -0,0 +1,7 @@
+use async_std::io::Cursor;
+use pin_cursor::PinCursor;
+
+fn main() {
+    let mut cursor = Box::pin(PinCursor::wrap(Cursor::new(&b"abc"[..])));
+    let _ = cursor.as_mut().write(b"d");
+}